@@ -20,7 +20,8 @@
 };
 use rustc_feature::Features;
 use rustc_lint_defs::builtin::PROC_MACRO_BACK_COMPAT;
-use rustc_lint_defs::{BufferedEarlyLint, BuiltinLintDiagnostics, RegisteredTools};
+use rustc_lint_defs::RegisteredTools;
+use rustc_lint_defs::{BufferedEarlyLint, BuiltinLintDiagnostics, Level as LintLevel};
 use rustc_parse::{parser, MACRO_ARGUMENTS};
 use rustc_session::errors::report_lit_error;
 use rustc_session::{parse::ParseSess, Limit, Session};
@@ -941,6 +942,9 @@ fn cfg_accessible(
     /// No caching is performed.
     fn get_proc_macro_quoted_span(&self, krate: CrateNum, id: usize) -> Span;
 
+    /// Returns the name of the specified crate.
+    fn crate_name(&self, krate: CrateNum) -> Symbol;
+
     /// The order of items in the HIR is unrelated to the order of
     /// items in the AST. However, we generate proc macro harnesses
     /// based on the AST order, and later refer to these harnesses
@@ -1001,6 +1005,16 @@ pub struct ExpansionData {
     /// Some parent node that is close to this macro call
     pub lint_node_id: NodeId,
     pub is_trailing_mac: bool,
+    /// For derive expansions, the paths of the other derives listed in the
+    /// same `#[derive(...)]` attribute, in source order. Empty otherwise.
+    pub derive_siblings: Lrc<[ast::Path]>,
+    /// For invocations inside of an `impl` block, including in items nested
+    /// in its functions, the span of the innermost such block and its self type.
+    pub enclosing_impl: Option<(Span, Lrc<ast::Ty>)>,
+    /// The levels set by lint attributes of the enclosing nodes, including
+    /// those of the invocation itself, for tool lints like `tool::lint` and
+    /// for the `warnings` group, outermost first.
+    pub tool_lint_levels: Lrc<[(Symbol, LintLevel)]>,
 }
 
 /// One of these is made during expansion and incrementally updated as we go;
@@ -1050,6 +1064,9 @@ pub fn new(
                 dir_ownership: DirOwnership::Owned { relative: None },
                 lint_node_id: ast::CRATE_NODE_ID,
                 is_trailing_mac: false,
+                derive_siblings: Default::default(),
+                enclosing_impl: None,
+                tool_lint_levels: Lrc::new([]),
             },
             force_mode: false,
             expansions: FxIndexMap::default(),
@@ -29,10 +29,10 @@
 };
 use rustc_parse::validate_attr;
 use rustc_session::lint::builtin::{UNUSED_ATTRIBUTES, UNUSED_DOC_COMMENTS};
-use rustc_session::lint::BuiltinLintDiagnostics;
+use rustc_session::lint::{BuiltinLintDiagnostics, Level as LintLevel};
 use rustc_session::parse::{feature_err, ParseSess};
 use rustc_session::Limit;
-use rustc_span::symbol::{sym, Ident};
+use rustc_span::symbol::{sym, Ident, Symbol};
 use rustc_span::{FileName, LocalExpnId, Span};
 
 use smallvec::SmallVec;
@@ -607,18 +607,28 @@ pub fn fully_expand_fragment(&mut self, input_fragment: AstFragment) -> AstFragm
                         .take_derive_resolutions(expn_id)
                         .map(|derives| {
                             derive_invocations.reserve(derives.len());
+                            let paths: Vec<ast::Path> =
+                                derives.iter().map(|(path, ..)| path.clone()).collect();
                             derives
                                 .into_iter()
-                                .map(|(path, item, _exts, is_const)| {
+                                .enumerate()
+                                .map(|(i, (path, item, _exts, is_const))| {
                                     // FIXME: Consider using the derive resolutions (`_exts`)
                                     // instead of enqueuing the derives to be resolved again later.
                                     let expn_id = LocalExpnId::fresh_empty();
+                                    let derive_siblings = paths
+                                        .iter()
+                                        .enumerate()
+                                        .filter(|&(j, _)| j != i)
+                                        .map(|(_, path)| path.clone())
+                                        .collect();
                                     derive_invocations.push((
                                         Invocation {
                                             kind: InvocationKind::Derive { path, item, is_const },
                                             fragment_kind,
                                             expansion_data: ExpansionData {
                                                 id: expn_id,
+                                                derive_siblings,
                                                 ..self.cx.current_expansion.clone()
                                             },
                                         },
@@ -1132,6 +1142,22 @@ macro_rules! assign_id {
     }};
 }
 
+/// Runs `$closure` with the levels set by the lint attributes among `$attrs`
+/// added to the tool lint levels of the current expansion, and restores the
+/// previous levels afterwards. `$consumed` tells whether the attributes go
+/// away with the node, see `tool_lint_levels`.
+macro_rules! with_tool_lint_levels {
+    ($self:ident, $attrs:expr, $consumed:expr, $closure:expr) => {{
+        let orig_levels = $self.cx.current_expansion.tool_lint_levels.clone();
+        if let Some(levels) = $self.tool_lint_levels($attrs, $consumed) {
+            $self.cx.current_expansion.tool_lint_levels = levels;
+        }
+        let ret = ($closure)();
+        $self.cx.current_expansion.tool_lint_levels = orig_levels;
+        ret
+    }};
+}
+
 enum AddSemicolon {
     Yes,
     No,
@@ -1212,6 +1238,14 @@ fn wrap_flat_map_node_noop_flat_map(
         collector: &mut InvocationCollector<'_, '_>,
         noop_flat_map: impl FnOnce(Self, &mut InvocationCollector<'_, '_>) -> Self::OutputTy,
     ) -> Result<Self::OutputTy, Self> {
+        if let ItemKind::Impl(impl_) = &node.kind {
+            let enclosing_impl = Some((node.span, Lrc::new((*impl_.self_ty).clone())));
+            let orig_enclosing_impl =
+                mem::replace(&mut collector.cx.current_expansion.enclosing_impl, enclosing_impl);
+            let res = noop_flat_map(node, collector);
+            collector.cx.current_expansion.enclosing_impl = orig_enclosing_impl;
+            return Ok(res);
+        }
         if !matches!(node.kind, ItemKind::Mod(..)) {
             return Ok(noop_flat_map(node, collector));
         }
@@ -1726,6 +1760,7 @@ fn collect(&mut self, fragment_kind: AstFragmentKind, kind: InvocationKind) -> A
                 expansion_data: ExpansionData {
                     id: expn_id,
                     depth: self.cx.current_expansion.depth + 1,
+                    derive_siblings: Default::default(),
                     ..self.cx.current_expansion.clone()
                 },
             },
@@ -1877,6 +1912,57 @@ fn expand_cfg_attr(&self, node: &mut impl HasAttrs, attr: &ast::Attribute, pos:
         });
     }
 
+    /// Returns the tool lint levels of the current expansion data extended by
+    /// the levels that the lint attributes among `attrs` set for lints of
+    /// registered tools and the `warnings` group, so that proc macros invoked
+    /// inside of the node with these attributes see them. Returns `None` if
+    /// there are no such attributes.
+    ///
+    /// An expectation is fulfilled once the lint level map knows about the
+    /// attribute, which never happens for the attributes of a macro invocation
+    /// since they are `consumed` with it. Those expectations are treated like
+    /// `allow`, as nothing could report them as unfulfilled either.
+    fn tool_lint_levels(
+        &self,
+        attrs: &[ast::Attribute],
+        consumed: bool,
+    ) -> Option<Lrc<[(Symbol, LintLevel)]>> {
+        let registered_tools = self.cx.resolver.registered_tools();
+        let mut levels = Vec::new();
+        for attr in attrs {
+            let Some(level) = LintLevel::from_attr(attr) else { continue };
+            let metas = attr.meta_item_list().unwrap_or_default();
+            for (lint_index, nested_meta) in metas.iter().enumerate() {
+                let Some(meta_item) = nested_meta.meta_item() else { continue };
+                let name = match &meta_item.path.segments[..] {
+                    [tool, lint]
+                        if registered_tools.contains(&Ident::with_dummy_span(tool.ident.name)) =>
+                    {
+                        Symbol::intern(&format!("{}::{}", tool.ident, lint.ident))
+                    }
+                    [lint] if lint.ident.as_str() == "warnings" => lint.ident.name,
+                    _ => continue,
+                };
+                let level = match level {
+                    LintLevel::Expect(_) if consumed => LintLevel::Allow,
+                    // Matches the ids the lint level map gives to the lints
+                    // of an `expect` attribute.
+                    LintLevel::Expect(mut id) => {
+                        id.set_lint_index(Some(lint_index as u16));
+                        LintLevel::Expect(id)
+                    }
+                    level => level,
+                };
+                levels.push((name, level));
+            }
+        }
+        if levels.is_empty() {
+            return None;
+        }
+        let orig_levels = self.cx.current_expansion.tool_lint_levels.iter().copied();
+        Some(orig_levels.chain(levels).collect())
+    }
+
     fn flat_map_node<Node: InvocationCollectorNode<OutputTy: Default>>(
         &mut self,
         mut node: Node,
@@ -1907,21 +1993,32 @@ fn flat_map_node<Node: InvocationCollectorNode<OutputTy: Default>>(
                     }
                     _ => {
                         Node::pre_flat_map_node_collect_attr(&self.cfg(), &attr);
-                        self.collect_attr((attr, pos, derives), node.to_annotatable(), Node::KIND)
+                        with_tool_lint_levels!(self, node.attrs(), true, || {
+                            self.collect_attr(
+                                (attr, pos, derives),
+                                node.to_annotatable(),
+                                Node::KIND,
+                            )
                             .make_ast::<Node>()
+                        })
                     }
                 },
                 None if node.is_mac_call() => {
                     let (mac, attrs, add_semicolon) = node.take_mac_call();
                     self.check_attributes(&attrs, &mac);
-                    let mut res = self.collect_bang(mac, Node::KIND).make_ast::<Node>();
+                    let mut res = with_tool_lint_levels!(self, &attrs, true, || {
+                        self.collect_bang(mac, Node::KIND).make_ast::<Node>()
+                    });
                     Node::post_flat_map_node_collect_bang(&mut res, add_semicolon);
                     res
                 }
                 None => {
-                    match Node::wrap_flat_map_node_noop_flat_map(node, self, |mut node, this| {
-                        assign_id!(this, node.node_id_mut(), || node.noop_flat_map(this))
-                    }) {
+                    let res = with_tool_lint_levels!(self, node.attrs(), false, || {
+                        Node::wrap_flat_map_node_noop_flat_map(node, self, |mut node, this| {
+                            assign_id!(this, node.node_id_mut(), || node.noop_flat_map(this))
+                        })
+                    });
+                    match res {
                         Ok(output) => output,
                         Err(returned_node) => {
                             node = returned_node;
@@ -1953,21 +2050,33 @@ fn visit_node<Node: InvocationCollectorNode<OutputTy = Node> + DummyAstNode>(
                         self.expand_cfg_attr(node, &attr, pos);
                         continue;
                     }
-                    _ => visit_clobber(node, |node| {
-                        self.collect_attr((attr, pos, derives), node.to_annotatable(), Node::KIND)
-                            .make_ast::<Node>()
-                    }),
+                    _ => {
+                        with_tool_lint_levels!(self, node.attrs(), true, || {
+                            visit_clobber(node, |node| {
+                                self.collect_attr(
+                                    (attr, pos, derives),
+                                    node.to_annotatable(),
+                                    Node::KIND,
+                                )
+                                .make_ast::<Node>()
+                            })
+                        })
+                    }
                 },
                 None if node.is_mac_call() => {
                     visit_clobber(node, |node| {
                         // Do not clobber unless it's actually a macro (uncommon case).
                         let (mac, attrs, _) = node.take_mac_call();
                         self.check_attributes(&attrs, &mac);
-                        self.collect_bang(mac, Node::KIND).make_ast::<Node>()
+                        with_tool_lint_levels!(self, &attrs, true, || {
+                            self.collect_bang(mac, Node::KIND).make_ast::<Node>()
+                        })
                     })
                 }
                 None => {
-                    assign_id!(self, node.node_id_mut(), || node.noop_visit(self))
+                    with_tool_lint_levels!(self, node.attrs(), false, || {
+                        assign_id!(self, node.node_id_mut(), || node.noop_visit(self))
+                    })
                 }
             };
         }
@@ -2115,6 +2224,8 @@ pub struct ExpansionConfig<'feat> {
     pub span_debug: bool,
     /// If true, show backtraces for proc-macro panics
     pub proc_macro_backtrace: bool,
+    /// If true, the crate being expanded has the `proc-macro` crate type
+    pub is_proc_macro_crate: bool,
 }
 
 impl ExpansionConfig<'_> {
@@ -2127,6 +2238,7 @@ pub fn default(crate_name: String, features: &Features) -> ExpansionConfig<'_> {
             should_test: false,
             span_debug: false,
             proc_macro_backtrace: false,
+            is_proc_macro_crate: false,
         }
     }
 }
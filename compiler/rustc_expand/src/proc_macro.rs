@@ -59,7 +59,8 @@ fn expand<'cx>(
 
         let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
         let strategy = exec_strategy(ecx);
-        let server = proc_macro_server::Rustc::new(ecx);
+        let server =
+            proc_macro_server::Rustc::new(ecx).with_input(&input).with_input_token_count(&[&input]);
         self.client.run(&strategy, server, input, proc_macro_backtrace).map_err(|e| {
             ecx.sess.emit_err(errors::ProcMacroPanicked {
                 span,
@@ -90,7 +91,9 @@ fn expand<'cx>(
 
         let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
         let strategy = exec_strategy(ecx);
-        let server = proc_macro_server::Rustc::new(ecx);
+        let server = proc_macro_server::Rustc::new(ecx)
+            .with_input(&annotation)
+            .with_input_token_count(&[&annotation, &annotated]);
         self.client.run(&strategy, server, annotation, annotated, proc_macro_backtrace).map_err(
             |e| {
                 let mut err = ecx.struct_span_err(span, "custom attribute panicked");
@@ -142,7 +145,7 @@ fn expand(
                 });
             let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
             let strategy = exec_strategy(ecx);
-            let server = proc_macro_server::Rustc::new(ecx);
+            let server = proc_macro_server::Rustc::new(ecx).with_input_token_count(&[&input]);
             match self.client.run(&strategy, server, input, proc_macro_backtrace) {
                 Ok(stream) => stream,
                 Err(e) => {
@@ -1,24 +1,33 @@
 use crate::base::ExtCtxt;
 use pm::bridge::{
-    server, DelimSpan, Diagnostic, ExpnGlobals, Group, Ident, LitKind, Literal, Punct, TokenTree,
+    server, CallerLocation, DelimSpan, Diagnostic, ExpnGlobals, Group, Ident, LitKind, Literal,
+    Punct, TokenEdit, TokenTree,
 };
-use pm::{Delimiter, Level};
+use pm::{CompilerChannel, Delimiter, Level, LineColumn, PortableSpan};
 use rustc_ast as ast;
 use rustc_ast::token;
 use rustc_ast::tokenstream::{self, DelimSpacing, Spacing, TokenStream};
 use rustc_ast::util::literal::escape_byte_str_symbol;
 use rustc_ast_pretty::pprust;
-use rustc_data_structures::fx::FxHashMap;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::sync::Lrc;
-use rustc_errors::{MultiSpan, PResult};
+use rustc_errors::{DiagnosticId, MultiSpan, PResult};
+use rustc_lexer::unescape::{self, CStrUnit, EscapeError, Mode};
+use rustc_lint_defs::Level as LintLevel;
 use rustc_parse::lexer::nfc_normalize;
-use rustc_parse::parse_stream_from_source_str;
+use rustc_parse::parser::ForceCollect;
+use rustc_parse::{maybe_file_to_stream, parse_stream_from_source_str};
+use rustc_session::config::{OutFileName, RemapPathScopeComponents};
 use rustc_session::parse::ParseSess;
+use rustc_session::RemapFileNameExt;
 use rustc_span::def_id::CrateNum;
+use rustc_span::hygiene::MacroKind;
 use rustc_span::symbol::{self, sym, Symbol};
-use rustc_span::{BytePos, FileName, Pos, SourceFile, Span};
+use rustc_span::{BytePos, ExpnKind, FileName, Pos, RelativeBytePos, SourceFile, Span};
 use smallvec::{smallvec, SmallVec};
+use std::cmp::Ordering;
 use std::ops::{Bound, Range};
+use std::path::Path;
 
 trait FromInternal<T> {
     fn from_internal(x: T) -> Self;
@@ -108,6 +117,7 @@ fn from_internal((stream, rustc): (TokenStream, &mut Rustc<'_, '_>)) -> Self {
                             close: span.close,
                             entire: span.entire(),
                         },
+                        interpolated: false,
                     }));
                     continue;
                 }
@@ -239,6 +249,7 @@ fn from_internal((stream, rustc): (TokenStream, &mut Rustc<'_, '_>)) -> Self {
                         delimiter: pm::Delimiter::Bracket,
                         stream: Some(stream),
                         span: DelimSpan::from_single(span),
+                        interpolated: false,
                     }));
                 }
 
@@ -265,6 +276,7 @@ fn from_internal((stream, rustc): (TokenStream, &mut Rustc<'_, '_>)) -> Self {
                             delimiter: pm::Delimiter::None,
                             stream: Some(stream),
                             span: DelimSpan::from_single(span),
+                            interpolated: true,
                         }))
                     }
                 }
@@ -328,7 +340,12 @@ fn from_internal((stream, rustc): (TokenStream, &mut Rustc<'_, '_>)) -> Self {
                     tokenstream::TokenTree::token_alone(kind, span)
                 }]
             }
-            TokenTree::Group(Group { delimiter, stream, span: DelimSpan { open, close, .. } }) => {
+            TokenTree::Group(Group {
+                delimiter,
+                stream,
+                span: DelimSpan { open, close, .. },
+                ..
+            }) => {
                 smallvec![tokenstream::TokenTree::Delimited(
                     tokenstream::DelimSpan { open, close },
                     DelimSpacing::new(Spacing::Alone, Spacing::Alone),
@@ -388,6 +405,374 @@ fn to_internal(self) -> rustc_errors::Level {
     }
 }
 
+/// Checks whether `stream` contains the identifier `name` as proc macros see
+/// it, i.e. including the identifiers of lifetimes, doc comments (`doc`) and
+/// interpolated tokens.
+fn stream_contains_ident(stream: &TokenStream, name: Symbol, is_raw: bool) -> bool {
+    stream.trees().any(|tree| match tree {
+        tokenstream::TokenTree::Delimited(.., tts) => stream_contains_ident(tts, name, is_raw),
+        tokenstream::TokenTree::Token(token, _) => match &token.kind {
+            token::Ident(sym, raw) => *sym == name && *raw == is_raw,
+            token::Lifetime(sym) => {
+                !is_raw && symbol::Ident::new(*sym, token.span).without_first_quote().name == name
+            }
+            token::DocComment(..) => !is_raw && name == sym::doc,
+            token::Interpolated(nt) => match &nt.0 {
+                token::NtIdent(ident, raw) => ident.name == name && *raw == is_raw,
+                nt => stream_contains_ident(&TokenStream::from_nonterminal_ast(nt), name, is_raw),
+            },
+            _ => false,
+        },
+    })
+}
+
+/// Counts the token trees in `stream` the way a proc macro sees them, with
+/// groups and interpolated fragments counting as one tree besides the trees
+/// they contain.
+fn count_tokens(stream: &TokenStream) -> usize {
+    stream
+        .trees()
+        .map(|tree| match tree {
+            tokenstream::TokenTree::Delimited(.., tts) => 1 + count_tokens(tts),
+            tokenstream::TokenTree::Token(token, _) => match &token.kind {
+                token::Interpolated(nt) => match &nt.0 {
+                    token::NtIdent(..) => 1,
+                    nt => 1 + count_tokens(&TokenStream::from_nonterminal_ast(nt)),
+                },
+                // Doc comments are passed as `#`, `!` and a bracketed group.
+                token::DocComment(_, ast::AttrStyle::Inner, _) => 6,
+                token::DocComment(..) => 5,
+                // Lifetimes are passed as a `'` punct and an ident.
+                token::Lifetime(..) => 2,
+                // Operators are split into one punct per character.
+                _ if token.is_punct() => pprust::token_kind_to_string(&token.kind).len(),
+                _ => 1,
+            },
+        })
+        .sum()
+}
+
+/// Appends the printed form of each token in `stream` to `tokens`, with the
+/// delimiters of groups as separate tokens. Invisible delimiters are skipped.
+fn flatten_tokens(stream: &TokenStream, tokens: &mut Vec<String>) {
+    for tree in stream.trees() {
+        match tree {
+            tokenstream::TokenTree::Token(token, _) => {
+                tokens.push(pprust::token_to_string(token).into_owned())
+            }
+            tokenstream::TokenTree::Delimited(.., delim, tts) => {
+                let (open, close) = (token::OpenDelim(*delim), token::CloseDelim(*delim));
+                let is_visible = *delim != token::Delimiter::Invisible;
+                if is_visible {
+                    tokens.push(pprust::token_kind_to_string(&open).into_owned());
+                }
+                flatten_tokens(tts, tokens);
+                if is_visible {
+                    tokens.push(pprust::token_kind_to_string(&close).into_owned());
+                }
+            }
+        }
+    }
+}
+
+/// Computes a shortest edit script turning `old` into `new`, using Myers'
+/// diff algorithm.
+fn token_edits(old: &[String], new: &[String]) -> Vec<TokenEdit> {
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = n + m;
+    // `v[k + max]` is the furthest `x` reached on diagonal `k = x - y`.
+    let mut v = vec![0isize; 2 * max as usize + 2];
+    // Only diagonals `-d..=d` can be reached with `d` edits, so the states
+    // are recorded for those, with `trace[d][k + d]` holding `v[k + max]`
+    // as it was before looking for paths of `d` edits.
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v[(max - d) as usize..=(max + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let i = (k + max) as usize;
+            let mut x =
+                if k == -d || (k != d && v[i - 1] < v[i + 1]) { v[i + 1] } else { v[i - 1] + 1 };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[i] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // Walk back through the recorded states to recover the edits.
+    let mut edits = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let (d, k) = (d as isize, x - y);
+        if d == 0 {
+            // The rest is a run of equal tokens from the start.
+            break;
+        }
+        let i = (k + d) as usize;
+        let prev_k = if k == -d || (k != d && v[i - 1] < v[i + 1]) { k + 1 } else { k - 1 };
+        let prev_x = v[(prev_k + d) as usize];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+        }
+        let (insert, index) = if x == prev_x { (true, prev_y) } else { (false, prev_x) };
+        let token = if insert { &new[index as usize] } else { &old[index as usize] };
+        edits.push(TokenEdit { insert, index: index as usize, token: token.clone() });
+        (x, y) = (prev_x, prev_y);
+    }
+    edits.reverse();
+    edits
+}
+
+/// The mode in which `rustc_lexer::unescape` processes the contents of
+/// literals of the given kind, or `None` for numeric literals.
+fn unescape_mode(kind: LitKind) -> Option<Mode> {
+    Some(match kind {
+        LitKind::Byte => Mode::Byte,
+        LitKind::Char => Mode::Char,
+        LitKind::Str => Mode::Str,
+        LitKind::StrRaw(_) => Mode::RawStr,
+        LitKind::ByteStr => Mode::ByteStr,
+        LitKind::ByteStrRaw(_) => Mode::RawByteStr,
+        LitKind::CStr => Mode::CStr,
+        LitKind::CStrRaw(_) => Mode::RawCStr,
+        LitKind::Integer | LitKind::Float | LitKind::Err => return None,
+    })
+}
+
+/// Describes an invalid escape found by `rustc_lexer::unescape`, in the
+/// words of the lexer's own diagnostics.
+fn escape_error_message(err: EscapeError) -> &'static str {
+    match err {
+        EscapeError::ZeroChars => "empty character literal",
+        EscapeError::MoreThanOneChar => "character literal may only contain one codepoint",
+        EscapeError::LoneSlash => "invalid trailing slash in literal",
+        EscapeError::InvalidEscape => "unknown character escape",
+        EscapeError::BareCarriageReturn | EscapeError::BareCarriageReturnInRawString => {
+            "bare CR not allowed in literal"
+        }
+        EscapeError::EscapeOnlyChar => "character constant must be escaped",
+        EscapeError::TooShortHexEscape => "numeric character escape is too short",
+        EscapeError::InvalidCharInHexEscape => "invalid character in numeric character escape",
+        EscapeError::OutOfRangeHexEscape => "out of range hex escape",
+        EscapeError::NoBraceInUnicodeEscape => "incorrect unicode escape sequence",
+        EscapeError::InvalidCharInUnicodeEscape => "invalid character in unicode escape",
+        EscapeError::EmptyUnicodeEscape => "empty unicode escape",
+        EscapeError::UnclosedUnicodeEscape => "unterminated unicode escape",
+        EscapeError::LeadingUnderscoreUnicodeEscape => "invalid start of unicode escape",
+        EscapeError::OverlongUnicodeEscape => "overlong unicode escape",
+        EscapeError::LoneSurrogateUnicodeEscape => "invalid unicode character escape",
+        EscapeError::OutOfRangeUnicodeEscape => "invalid unicode character escape",
+        EscapeError::UnicodeEscapeInByte => "unicode escape in byte string",
+        EscapeError::NonAsciiCharInByte => "non-ASCII character in byte literal",
+        EscapeError::UnskippedWhitespaceWarning => "whitespace symbol is not skipped",
+        EscapeError::MultipleSkippedLinesWarning => "multiple lines skipped by escaped newline",
+    }
+}
+
+/// Resolves byte positions within a file to lines and columns, like
+/// `SourceMap::lookup_char_pos`. Positions have to be passed in increasing
+/// order, which allows walking the file's line table and multibyte characters
+/// once instead of searching them for every position.
+struct LineCursor {
+    file: Lrc<SourceFile>,
+    /// The index of the current line in `file.lines()`.
+    line: usize,
+    /// The number of multibyte characters before the last position, and the
+    /// number of extra bytes they take up.
+    chars: usize,
+    extra_bytes: u32,
+    /// The same, for the start of the current line.
+    line_chars: usize,
+    line_extra_bytes: u32,
+}
+
+impl LineCursor {
+    fn new(file: Lrc<SourceFile>) -> Self {
+        LineCursor { file, line: 0, chars: 0, extra_bytes: 0, line_chars: 0, line_extra_bytes: 0 }
+    }
+
+    fn advance_to(&mut self, pos: BytePos) -> LineColumn {
+        let pos = self.file.relative_position(pos);
+        let lines = self.file.lines();
+        while self.line + 1 < lines.len() && lines[self.line + 1] <= pos {
+            self.line += 1;
+        }
+        // Empty files have no lines, which is reported as line 0.
+        let (line, line_start) = match lines.get(self.line) {
+            Some(&line_start) => (self.line + 1, line_start),
+            None => (0, RelativeBytePos(0)),
+        };
+
+        let multibyte_chars = &self.file.multibyte_chars;
+        let skip_chars = |chars: &mut usize, extra_bytes: &mut u32, until: RelativeBytePos| {
+            while let Some(mbc) = multibyte_chars.get(*chars).filter(|mbc| mbc.pos < until) {
+                *extra_bytes += mbc.bytes as u32 - 1;
+                *chars += 1;
+            }
+        };
+        skip_chars(&mut self.line_chars, &mut self.line_extra_bytes, line_start);
+        skip_chars(&mut self.chars, &mut self.extra_bytes, pos);
+
+        let column =
+            (pos.to_u32() - self.extra_bytes) - (line_start.to_u32() - self.line_extra_bytes);
+        LineColumn { line, column: column as usize + 1 }
+    }
+}
+
+/// The kind of the last token written by a `TokenPrinter`, as far as it
+/// matters for deciding what whitespace has to follow it.
+#[derive(Clone, Copy, PartialEq)]
+enum PrintedKind {
+    Ident,
+    Literal,
+    Punct,
+    Open,
+    Close,
+    Other,
+}
+
+/// Token printer used by `TokenStream::to_string_formatted`.
+///
+/// `pprust::tts_to_string` always separates tokens the same way. This printer
+/// instead either emits only the whitespace needed for the output to lex back
+/// into the same tokens (`minify`), or puts brace-delimited blocks and
+/// statements on their own indented lines.
+struct TokenPrinter {
+    out: String,
+    minify: bool,
+    indent: usize,
+    /// The last character written, the kind of token it belongs to, and
+    /// whether that token is joint with the next one.
+    prev: Option<(char, PrintedKind, bool)>,
+    /// Whether a line break has to be written before the next token.
+    newline: bool,
+}
+
+impl TokenPrinter {
+    fn new(minify: bool) -> Self {
+        TokenPrinter { out: String::new(), minify, indent: 0, prev: None, newline: false }
+    }
+
+    /// Prints `stream`. `in_block` is true if the stream is at the top level
+    /// or directly inside braces, i.e. where items and statements appear.
+    fn print_stream(&mut self, stream: &TokenStream, in_block: bool) {
+        for tree in stream.trees() {
+            match tree {
+                tokenstream::TokenTree::Token(token, spacing) => {
+                    let kind = match token.kind {
+                        token::Ident(..) | token::Lifetime(_) | token::Interpolated(_) => {
+                            PrintedKind::Ident
+                        }
+                        token::Literal(_) => PrintedKind::Literal,
+                        _ if token.is_punct() => PrintedKind::Punct,
+                        _ => PrintedKind::Other,
+                    };
+                    let joint = *spacing != Spacing::Alone;
+                    self.print_token(&pprust::token_to_string(token), kind, joint);
+                    match token.kind {
+                        // Anything after a line comment on the same line would
+                        // become part of the comment.
+                        token::DocComment(token::CommentKind::Line, ..) => self.newline = true,
+                        token::Semi if in_block && !self.minify => self.newline = true,
+                        _ => {}
+                    }
+                }
+                tokenstream::TokenTree::Delimited(_, _, delim, tts) => {
+                    let (open, close) = match delim {
+                        token::Delimiter::Parenthesis => ("(", ")"),
+                        token::Delimiter::Brace => ("{", "}"),
+                        token::Delimiter::Bracket => ("[", "]"),
+                        token::Delimiter::Invisible => {
+                            self.print_stream(tts, false);
+                            continue;
+                        }
+                    };
+                    let is_brace = *delim == token::Delimiter::Brace;
+                    self.print_token(open, PrintedKind::Open, false);
+                    if is_brace && !self.minify && !tts.is_empty() {
+                        self.indent += 1;
+                        self.newline = true;
+                        self.print_stream(tts, true);
+                        self.indent -= 1;
+                        self.newline = true;
+                    } else {
+                        self.print_stream(tts, is_brace);
+                    }
+                    self.print_token(close, PrintedKind::Close, false);
+                    if is_brace && in_block && !self.minify {
+                        self.newline = true;
+                    }
+                }
+            }
+        }
+    }
+
+    fn print_token(&mut self, text: &str, kind: PrintedKind, joint: bool) {
+        let Some(first) = text.chars().next() else { return };
+        if self.newline {
+            if self.minify {
+                self.out.push('\n');
+            } else if text == "else" {
+                self.out.push(' ');
+            } else if !matches!(text, "," | ";" | ".") {
+                self.out.push('\n');
+                self.out.extend(std::iter::repeat("    ").take(self.indent));
+            }
+        } else if let Some(prev) = self.prev {
+            if self.needs_space(prev, first, kind) {
+                self.out.push(' ');
+            }
+        }
+        self.newline = false;
+        self.out.push_str(text);
+        self.prev = text.chars().last().map(|last| (last, kind, joint));
+    }
+
+    fn needs_space(
+        &self,
+        (prev, prev_kind, prev_joint): (char, PrintedKind, bool),
+        next: char,
+        kind: PrintedKind,
+    ) -> bool {
+        if prev_kind == PrintedKind::Open || kind == PrintedKind::Close {
+            return false;
+        }
+
+        let is_ident_char = |c: char| c.is_alphanumeric() || c == '_' || !c.is_ascii();
+        let must_separate = match (prev_kind, kind) {
+            // `a b`, `1 u8`
+            _ if is_ident_char(prev) && is_ident_char(next) => true,
+            // `/ /` and `/ *` would start a comment.
+            _ if prev == '/' && (next == '/' || next == '*') => true,
+            // `+ =`
+            (PrintedKind::Punct, PrintedKind::Punct) => !prev_joint,
+            // `r #x`, `b "x"`, `b 'x'`
+            (PrintedKind::Ident, _) => matches!(next, '#' | '"' | '\''),
+            // `"x" as`, `'c' r"y"`: an identifier directly after a literal
+            // would become its suffix. `1 .0`, `1. x`
+            (PrintedKind::Literal, _) => is_ident_char(next) || next == '.' || prev == '.',
+            _ => false,
+        };
+        if must_separate || self.minify {
+            return must_separate;
+        }
+
+        match (prev_kind, kind) {
+            (PrintedKind::Punct, _) if prev_joint || prev == '.' => false,
+            (_, PrintedKind::Punct) => !matches!(next, ',' | ';' | ':' | '.' | '?' | '!'),
+            (PrintedKind::Ident, PrintedKind::Open) => next == '{',
+            _ => true,
+        }
+    }
+}
+
 pub struct FreeFunctions;
 
 pub(crate) struct Rustc<'a, 'b> {
@@ -397,6 +782,11 @@ pub(crate) struct Rustc<'a, 'b> {
     mixed_site: Span,
     krate: CrateNum,
     rebased_spans: FxHashMap<usize, Span>,
+    input_span: Option<Span>,
+    input_token_count: usize,
+    /// The spans and messages of the diagnostics emitted through
+    /// `emit_diagnostic_deduplicated`.
+    emitted_diagnostics: FxHashSet<(Vec<Span>, String)>,
 }
 
 impl<'a, 'b> Rustc<'a, 'b> {
@@ -408,13 +798,86 @@ pub fn new(ecx: &'a mut ExtCtxt<'b>) -> Self {
             mixed_site: ecx.with_mixed_site_ctxt(expn_data.call_site),
             krate: expn_data.macro_def_id.unwrap().krate,
             rebased_spans: FxHashMap::default(),
+            input_span: None,
+            input_token_count: 0,
+            emitted_diagnostics: FxHashSet::default(),
             ecx,
         }
     }
 
+    /// Records the extent of the tokens passed to the macro, which is
+    /// reported by `invocation_args_span`.
+    pub fn with_input(mut self, input: &TokenStream) -> Self {
+        let mut trees = input.trees();
+        if let Some(first) = trees.next() {
+            let last = trees.last().unwrap_or(first);
+            self.input_span = Some(first.span().to(last.span()));
+        }
+        self
+    }
+
+    /// Records the number of tokens in all streams passed to the macro, which
+    /// is reported by `input_token_count`.
+    pub fn with_input_token_count(mut self, inputs: &[&TokenStream]) -> Self {
+        self.input_token_count = inputs.iter().map(|input| count_tokens(input)).sum();
+        self
+    }
+
     fn sess(&self) -> &ParseSess {
         self.ecx.parse_sess()
     }
+
+    /// Renders the primary message of `diag`, for errors which are returned to
+    /// the proc macro rather than emitted.
+    fn diagnostic_message(&self, diag: &rustc_errors::Diagnostic) -> String {
+        let (message, _) = &diag.styled_message()[0];
+        self.sess().dcx.eagerly_translate_to_string(message.clone(), diag.args())
+    }
+
+    /// Determines the level of a diagnostic belonging to the tool lint `name`
+    /// from the `-A`/`-W`/`-D`/`-F` options and the lint attributes around the
+    /// invocation, returning `None` if the lint is allowed. Like for other
+    /// lints, a warning takes the level of the `warnings` group if it is set,
+    /// and `--cap-lints` applies. An expected lint is emitted at the `Expect`
+    /// level, which fulfills the expectation without showing the diagnostic.
+    ///
+    /// The lint level map is only built once expansion is done, so the levels
+    /// are taken from the attributes collected in `ExpansionData` instead.
+    fn tool_lint_level(&self, name: &str, level: Level) -> Option<rustc_errors::Level> {
+        let opts = &self.ecx.sess.opts;
+        // Attributes override the command line and inner attributes override
+        // outer ones, unless the lint is forbidden.
+        let find_level = |name: &str| {
+            let cli_level = opts.lint_opts.iter().rev().find(|(lint, _)| lint == name);
+            let mut found = cli_level.map(|&(_, lint_level)| lint_level);
+            for &(lint, lint_level) in self.ecx.current_expansion.tool_lint_levels.iter() {
+                if lint.as_str() == name && found != Some(LintLevel::Forbid) {
+                    found = Some(lint_level);
+                }
+            }
+            found
+        };
+        let mut lint_level = match find_level(name) {
+            Some(lint_level) => lint_level,
+            None => match level {
+                Level::Error => LintLevel::Deny,
+                Level::Warning => LintLevel::Warn,
+                _ => return Some(level.to_internal()),
+            },
+        };
+        if lint_level == LintLevel::Warn {
+            lint_level = find_level("warnings").unwrap_or(LintLevel::Warn);
+        }
+        if let Some(cap) = opts.lint_cap {
+            lint_level = lint_level.min(cap);
+        }
+        match lint_level {
+            LintLevel::Allow => None,
+            LintLevel::Expect(id) => Some(rustc_errors::Level::Expect(id)),
+            LintLevel::Warn | LintLevel::ForceWarn(_) => Some(rustc_errors::Level::Warning(None)),
+            LintLevel::Deny | LintLevel::Forbid => Some(rustc_errors::Level::Error { lint: true }),
+        }
+    }
 }
 
 impl server::Types for Rustc<'_, '_> {
@@ -495,9 +958,24 @@ fn literal_from_str(&mut self, s: &str) -> Result<Literal<Self::Span, Self::Symb
     }
 
     fn emit_diagnostic(&mut self, diagnostic: Diagnostic<Self::Span>) {
-        let mut diag =
-            rustc_errors::Diagnostic::new(diagnostic.level.to_internal(), diagnostic.message);
+        let level = match &diagnostic.tool_lint {
+            Some(lint) => match self.tool_lint_level(lint, diagnostic.level) {
+                Some(level) => level,
+                None => return,
+            },
+            None => diagnostic.level.to_internal(),
+        };
+        let mut diag = rustc_errors::Diagnostic::new(level, diagnostic.message);
         diag.set_span(MultiSpan::from_spans(diagnostic.spans));
+        // Only lints end up in the future incompatibility report, which is
+        // why `future_incompatible` always sets a tool lint.
+        if let Some(name) = diagnostic.tool_lint.filter(|_| diagnostic.future_incompatible) {
+            diag.set_is_lint().code(DiagnosticId::Lint {
+                name,
+                has_future_breakage: true,
+                is_force_warn: false,
+            });
+        }
         for child in diagnostic.children {
             diag.sub(
                 child.level.to_internal(),
@@ -508,6 +986,143 @@ fn emit_diagnostic(&mut self, diagnostic: Diagnostic<Self::Span>) {
         }
         self.sess().dcx.emit_diagnostic(diag);
     }
+
+    fn emit_diagnostics(&mut self, diagnostics: Vec<Diagnostic<Self::Span>>) {
+        for diagnostic in diagnostics {
+            self.emit_diagnostic(diagnostic);
+        }
+    }
+
+    fn emit_diagnostic_deduplicated(&mut self, diagnostic: Diagnostic<Self::Span>) {
+        let key = (diagnostic.spans.clone(), diagnostic.message.clone());
+        if self.emitted_diagnostics.insert(key) {
+            self.emit_diagnostic(diagnostic);
+        }
+    }
+
+    fn derive_siblings(&mut self) -> Vec<String> {
+        self.ecx.current_expansion.derive_siblings.iter().map(pprust::path_to_string).collect()
+    }
+
+    fn invocation_args_span(&mut self) -> Self::Span {
+        self.input_span.unwrap_or(self.call_site)
+    }
+
+    fn validate_literal(
+        &mut self,
+        literal: Literal<Self::Span, Self::Symbol>,
+    ) -> Result<(), String> {
+        let Some(mode) = unescape_mode(literal.kind) else {
+            return Ok(());
+        };
+
+        let src = literal.symbol.as_str();
+        let mut error = None;
+        let mut check = |range: Range<usize>, err: Option<EscapeError>| match err {
+            Some(err) if err.is_fatal() && error.is_none() => error = Some((range, err)),
+            _ => {}
+        };
+        match mode {
+            Mode::CStr | Mode::RawCStr => {
+                unescape::unescape_c_string(src, mode, &mut |range, res| check(range, res.err()))
+            }
+            _ => unescape::unescape_literal(src, mode, &mut |range, res| check(range, res.err())),
+        }
+        match error {
+            Some((range, err)) => Err(format!("{}: `{}`", escape_error_message(err), &src[range])),
+            None => Ok(()),
+        }
+    }
+
+    fn unescape_literal(&mut self, literal: Literal<Self::Span, Self::Symbol>) -> Option<Vec<u8>> {
+        let mode = unescape_mode(literal.kind)?;
+        let src = literal.symbol.as_str();
+        let mut out = Vec::with_capacity(src.len());
+        let mut valid = true;
+        let push_char = |out: &mut Vec<u8>, c: char| {
+            out.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes())
+        };
+        match mode {
+            Mode::CStr | Mode::RawCStr => {
+                unescape::unescape_c_string(src, mode, &mut |_, res| match res {
+                    Ok(CStrUnit::Byte(b)) => out.push(b),
+                    Ok(CStrUnit::Char(c)) => push_char(&mut out, c),
+                    Err(err) => valid &= !err.is_fatal(),
+                })
+            }
+            // Escapes like `\xFF` in byte literals are returned as the
+            // character with the byte's value.
+            Mode::Byte | Mode::ByteStr | Mode::RawByteStr => {
+                unescape::unescape_literal(src, mode, &mut |_, res| match res {
+                    Ok(c) => out.push(c as u8),
+                    Err(err) => valid &= !err.is_fatal(),
+                })
+            }
+            _ => unescape::unescape_literal(src, mode, &mut |_, res| match res {
+                Ok(c) => push_char(&mut out, c),
+                Err(err) => valid &= !err.is_fatal(),
+            }),
+        }
+        valid.then_some(out)
+    }
+
+    fn is_proc_macro_crate(&mut self) -> bool {
+        self.ecx.ecfg.is_proc_macro_crate
+    }
+
+    fn feature_enabled(&mut self, feature: &str) -> bool {
+        self.ecx.ecfg.features.declared(Symbol::intern(feature))
+    }
+
+    fn macro_crate_name(&mut self) -> String {
+        self.ecx.resolver.crate_name(self.krate).to_string()
+    }
+
+    fn input_token_count(&mut self) -> usize {
+        self.input_token_count
+    }
+
+    fn enclosing_impl_span(&mut self) -> Option<Self::Span> {
+        self.ecx.current_expansion.enclosing_impl.as_ref().map(|(span, _)| *span)
+    }
+
+    fn enclosing_impl_self_ty(&mut self) -> Option<Self::TokenStream> {
+        let (_, self_ty) = self.ecx.current_expansion.enclosing_impl.clone()?;
+        let src = pprust::ty_to_string(&self_ty);
+        Some(parse_stream_from_source_str(
+            FileName::proc_macro_source_code(&src),
+            src,
+            self.sess(),
+            Some(self_ty.span),
+        ))
+    }
+
+    fn compiler_channel(&mut self) -> CompilerChannel {
+        match option_env!("CFG_RELEASE_CHANNEL") {
+            Some("stable") => CompilerChannel::Stable,
+            Some("beta") => CompilerChannel::Beta,
+            Some("nightly") => CompilerChannel::Nightly,
+            _ => CompilerChannel::Dev,
+        }
+    }
+
+    fn target_pointer_width(&mut self) -> u32 {
+        self.sess().target.pointer_width
+    }
+
+    fn out_dir(&mut self) -> Option<String> {
+        let io = &self.ecx.sess.io;
+        // `-o` takes precedence over `--out-dir`, as it does for the output.
+        let dir = match (&io.output_file, &io.output_dir) {
+            (Some(OutFileName::Real(file)), _) => match file.parent()? {
+                parent if parent.as_os_str().is_empty() => Path::new("."),
+                parent => parent,
+            },
+            (_, Some(dir)) => dir.as_path(),
+            _ => return None,
+        };
+        dir.to_str().map(str::to_owned)
+    }
 }
 
 impl server::TokenStream for Rustc<'_, '_> {
@@ -524,10 +1139,48 @@ fn from_str(&mut self, src: &str) -> Self::TokenStream {
         )
     }
 
+    fn type_path(&mut self, path: &str) -> Result<Self::TokenStream, String> {
+        let source_file = self
+            .sess()
+            .source_map()
+            .new_source_file(FileName::proc_macro_source_code(path), path.to_owned());
+        let stream = maybe_file_to_stream(self.sess(), source_file, Some(self.call_site))
+            .map_err(|errs| self.diagnostic_message(&errs[0]))?;
+        let result: PResult<'_, _> = try {
+            let mut p = rustc_parse::stream_to_parser(
+                self.sess(),
+                stream.clone(),
+                Some("proc_macro type path"),
+            );
+            p.parse_nonterminal(token::NonterminalKind::Path)?;
+            if p.token != token::Eof {
+                p.unexpected()?;
+            }
+        };
+        match result {
+            Ok(()) => Ok(stream),
+            Err(err) => {
+                let message = self.diagnostic_message(&err);
+                err.cancel();
+                Err(message)
+            }
+        }
+    }
+
     fn to_string(&mut self, stream: &Self::TokenStream) -> String {
         pprust::tts_to_string(stream)
     }
 
+    fn to_string_formatted(&mut self, stream: &Self::TokenStream, minify: bool) -> String {
+        let mut printer = TokenPrinter::new(minify);
+        printer.print_stream(stream, true);
+        printer.out
+    }
+
+    fn contains_ident(&mut self, stream: &Self::TokenStream, name: &str, is_raw: bool) -> bool {
+        stream_contains_ident(stream, nfc_normalize(name), is_raw)
+    }
+
     fn expand_expr(&mut self, stream: &Self::TokenStream) -> Result<Self::TokenStream, ()> {
         // Parse the expression from our tokenstream.
         let expr: PResult<'_, _> = try {
@@ -633,6 +1286,142 @@ fn into_trees(
     ) -> Vec<TokenTree<Self::TokenStream, Self::Span, Self::Symbol>> {
         FromInternal::from_internal((stream, self))
     }
+
+    fn unwrap_single(&mut self, stream: Self::TokenStream) -> Self::TokenStream {
+        if stream.len() != 1 {
+            return stream;
+        }
+        let inner = match stream.trees().next() {
+            Some(tokenstream::TokenTree::Delimited(.., token::Delimiter::Invisible, tts)) => {
+                tts.clone()
+            }
+            // Nonterminals are passed to proc macros as invisible groups.
+            Some(tokenstream::TokenTree::Token(token, _)) => match &token.kind {
+                token::Interpolated(nt) => TokenStream::from_nonterminal_ast(&nt.0),
+                _ => return stream,
+            },
+            _ => return stream,
+        };
+        self.unwrap_single(inner)
+    }
+
+    fn diff(
+        &mut self,
+        old: Option<Self::TokenStream>,
+        new: Option<Self::TokenStream>,
+    ) -> Vec<TokenEdit> {
+        let (mut old_tokens, mut new_tokens) = (Vec::new(), Vec::new());
+        flatten_tokens(&old.unwrap_or_default(), &mut old_tokens);
+        flatten_tokens(&new.unwrap_or_default(), &mut new_tokens);
+        token_edits(&old_tokens, &new_tokens)
+    }
+
+    fn repeat(
+        &mut self,
+        template: Option<Self::TokenStream>,
+        count: usize,
+        separator: Option<TokenTree<Self::TokenStream, Self::Span, Self::Symbol>>,
+    ) -> Self::TokenStream {
+        let template = template.unwrap_or_default();
+        let separator = match separator {
+            Some(tree) => (tree, &mut *self).to_internal(),
+            None => SmallVec::new(),
+        };
+        let mut stream = tokenstream::TokenStream::default();
+        for i in 0..count {
+            if i > 0 {
+                for tt in &separator {
+                    stream.push_tree(tt.clone());
+                }
+            }
+            stream.push_stream(template.clone());
+        }
+        stream
+    }
+
+    fn first_kind(&mut self, stream: &Self::TokenStream) -> Option<u8> {
+        // The numbering is the one of `proc_macro::TokenTreeKind`, and the
+        // classification has to match the conversion in `from_internal`.
+        let kind = match stream.trees().next()? {
+            tokenstream::TokenTree::Delimited(..) => 0,
+            tokenstream::TokenTree::Token(token, _) => match &token.kind {
+                token::Ident(..) => 1,
+                token::Literal(..) => 3,
+                token::Interpolated(nt) => match &nt.0 {
+                    token::NtIdent(..) => 1,
+                    nt if crate::base::nt_pretty_printing_compatibility_hack(nt, self.sess()) => {
+                        return self.first_kind(&TokenStream::from_nonterminal_ast(nt));
+                    }
+                    _ => 0,
+                },
+                // Lifetimes and doc comments start with a `'` or `#` punct.
+                _ => 2,
+            },
+        };
+        Some(kind)
+    }
+
+    fn validate_as_item(&mut self, stream: &Self::TokenStream) -> Result<(), String> {
+        let result: PResult<'_, _> = try {
+            let mut p =
+                rustc_parse::stream_to_parser(self.sess(), stream.clone(), Some("proc_macro item"));
+            while p.token != token::Eof {
+                if p.parse_item(ForceCollect::No)?.is_none() {
+                    p.unexpected()?;
+                }
+            }
+        };
+        result.map_err(|err| {
+            let message = self.diagnostic_message(&err);
+            err.cancel();
+            message
+        })
+    }
+
+    fn validate_as_expr(&mut self, stream: &Self::TokenStream) -> Result<(), String> {
+        let result: PResult<'_, _> = try {
+            let mut p = rustc_parse::stream_to_parser(
+                self.sess(),
+                stream.clone(),
+                Some("proc_macro expression"),
+            );
+            p.parse_expr()?;
+            if p.token != token::Eof {
+                p.unexpected()?;
+            }
+        };
+        result.map_err(|err| {
+            let message = self.diagnostic_message(&err);
+            err.cancel();
+            message
+        })
+    }
+
+    fn nth_span(&mut self, stream: &Self::TokenStream, n: usize) -> Option<Self::Span> {
+        let mut n = n;
+        for tree in stream.trees() {
+            // Only tokens can turn into several token trees for the client,
+            // e.g. `+=` or a lifetime, so just these are converted to count
+            // them, rather than the whole stream.
+            if let tokenstream::TokenTree::Delimited(span, ..) = tree {
+                if n == 0 {
+                    return Some(span.entire());
+                }
+                n -= 1;
+                continue;
+            }
+            let trees: Vec<TokenTree<_, _, _>> =
+                FromInternal::from_internal((TokenStream::new(vec![tree.clone()]), &mut *self));
+            match trees.get(n) {
+                Some(TokenTree::Group(group)) => return Some(group.span.entire),
+                Some(TokenTree::Punct(punct)) => return Some(punct.span),
+                Some(TokenTree::Ident(ident)) => return Some(ident.span),
+                Some(TokenTree::Literal(literal)) => return Some(literal.span),
+                None => n -= trees.len(),
+            }
+        }
+        None
+    }
 }
 
 impl server::SourceFile for Rustc<'_, '_> {
@@ -655,6 +1444,36 @@ fn path(&mut self, file: &Self::SourceFile) -> String {
     fn is_real(&mut self, file: &Self::SourceFile) -> bool {
         file.is_real_file()
     }
+
+    fn source_file_at(&mut self, pos: u32) -> Option<Self::SourceFile> {
+        let pos = BytePos(pos);
+        let file = self.sess().source_map().lookup_source_file(pos);
+        // The lookup clamps to the last file for positions past the end.
+        file.contains(pos).then_some(file)
+    }
+
+    fn register_virtual(&mut self, name: &str, contents: String) -> Self::SourceFile {
+        self.sess().source_map().new_source_file(FileName::Custom(name.to_owned()), contents)
+    }
+
+    fn span(&mut self, file: &Self::SourceFile, range: Range<usize>) -> Option<Self::Span> {
+        if range.start > range.end || range.end > file.source_len.to_usize() {
+            return None;
+        }
+        if let Some(src) = &file.src {
+            if !src.is_char_boundary(range.start) || !src.is_char_boundary(range.end) {
+                return None;
+            }
+        }
+        let lo = file.start_pos + BytePos::from_usize(range.start);
+        let hi = file.start_pos + BytePos::from_usize(range.end);
+        Some(Span::new(lo, hi, self.call_site.ctxt(), None))
+    }
+
+    fn path_cmp(&mut self, file: &Self::SourceFile, other: &Self::SourceFile) -> Ordering {
+        let path = |file: &SourceFile| file.name.prefer_local().to_string();
+        path(file).cmp(&path(other))
+    }
 }
 
 impl server::Span for Rustc<'_, '_> {
@@ -795,6 +1614,183 @@ fn recover_proc_macro_span(&mut self, id: usize) -> Self::Span {
             resolver.get_proc_macro_quoted_span(krate, id).with_ctxt(def_site.ctxt())
         })
     }
+
+    fn is_generated(&mut self, span: Self::Span) -> bool {
+        let ctxt = span.ctxt();
+        ctxt == self.def_site.ctxt() || ctxt == self.mixed_site.ctxt()
+    }
+
+    fn expansion_name(&mut self, span: Self::Span) -> Option<String> {
+        let expn_data = span.ctxt().outer_expn_data();
+        match expn_data.kind {
+            ExpnKind::Macro(..) => Some(expn_data.kind.descr()),
+            ExpnKind::Root | ExpnKind::AstPass(_) | ExpnKind::Desugaring(_) => None,
+        }
+    }
+
+    fn truncate(&mut self, span: Self::Span, max_bytes: usize) -> Self::Span {
+        if (span.hi() - span.lo()).to_usize() <= max_bytes {
+            return span;
+        }
+        // Don't cut through a multi-byte character if the source is available.
+        let mut len = max_bytes;
+        if let Ok(snippet) = self.sess().source_map().span_to_snippet(span) {
+            while !snippet.is_char_boundary(len) {
+                len -= 1;
+            }
+        }
+        span.with_hi(span.lo() + BytePos::from_usize(len))
+    }
+
+    fn contains(&mut self, span: Self::Span, other: Self::Span) -> bool {
+        // Byte positions are global to the source map, so this is false for
+        // spans in different files.
+        span.contains(other)
+    }
+
+    fn with_source_text(&mut self, span: Self::Span, text: &str) -> Self::Span {
+        let source_map = self.sess().source_map();
+        if source_map.span_to_snippet(span).is_ok_and(|snippet| snippet == text) {
+            return span;
+        }
+        let file =
+            source_map.new_source_file(FileName::proc_macro_source_code(text), text.to_owned());
+        Span::new(file.start_pos, file.end_position(), span.ctxt(), span.parent())
+    }
+
+    fn locations(&mut self, spans: Vec<Self::Span>) -> Vec<Range<LineColumn>> {
+        let mut positions: Vec<(BytePos, usize)> =
+            spans.iter().flat_map(|span| [span.lo(), span.hi()]).zip(0..).collect();
+        positions.sort_unstable();
+
+        let source_map = self.sess().source_map();
+        let mut locations = vec![LineColumn { line: 0, column: 0 }; positions.len()];
+        let mut cursor: Option<LineCursor> = None;
+        for (pos, i) in positions {
+            if !cursor.as_ref().is_some_and(|cursor| cursor.file.contains(pos)) {
+                cursor = Some(LineCursor::new(source_map.lookup_source_file(pos)));
+            }
+            locations[i] = cursor.as_mut().unwrap().advance_to(pos);
+        }
+        locations.chunks(2).map(|pair| pair[0]..pair[1]).collect()
+    }
+
+    fn caller_location(&mut self, span: Self::Span) -> CallerLocation {
+        let loc = self.sess().source_map().lookup_char_pos(span.lo());
+        let file = loc.file.name.for_scope(self.ecx.sess, RemapPathScopeComponents::DIAGNOSTICS);
+        CallerLocation {
+            file: file.to_string_lossy().into_owned(),
+            line: loc.line as u32,
+            column: loc.col_display as u32 + 1,
+        }
+    }
+
+    fn source_cmp(&mut self, span: Self::Span, other: Self::Span) -> Ordering {
+        // All files share one address space, with positions increasing in the
+        // order in which the files were loaded.
+        (span.lo(), span.hi()).cmp(&(other.lo(), other.hi()))
+    }
+
+    fn line_text(&mut self, span: Self::Span) -> Option<String> {
+        if span.is_dummy() {
+            return None;
+        }
+        let source_map = self.sess().source_map();
+        let loc = source_map.lookup_char_pos(span.lo());
+        if !loc.file.is_real_file() || !source_map.ensure_source_file_source_present(&loc.file) {
+            return None;
+        }
+        let line = loc.file.get_line(loc.line - 1)?;
+        Some(line.strip_suffix('\r').unwrap_or(&line).to_owned())
+    }
+
+    fn point(&mut self, span: Self::Span) -> Self::Span {
+        self.sess().source_map().start_point(span)
+    }
+
+    fn extend_to_next_token(&mut self, span: Self::Span) -> Self::Span {
+        let Ok(next_source) = self.sess().source_map().span_to_next_source(span) else {
+            return span;
+        };
+        match next_source.find(|c: char| !c.is_whitespace()) {
+            Some(n) => span.with_hi(span.hi() + BytePos::from_usize(n)),
+            None => span,
+        }
+    }
+
+    fn to_end_of_line(&mut self, span: Self::Span) -> Self::Span {
+        let source_map = self.sess().source_map();
+        let loc = source_map.lookup_char_pos(span.lo());
+        if !loc.file.is_real_file() || !source_map.ensure_source_file_source_present(&loc.file) {
+            return span;
+        }
+        let Some(line) = loc.file.get_line(loc.line - 1) else {
+            return span;
+        };
+        let line_start = loc.file.line_bounds(loc.line - 1).start;
+        let line_len = line.strip_suffix('\r').unwrap_or(&line).len();
+        span.with_hi(line_start + BytePos::from_usize(line_len))
+    }
+
+    fn same_expansion(&mut self, span: Self::Span, other: Self::Span) -> bool {
+        span.ctxt().outer_expn() == other.ctxt().outer_expn()
+    }
+
+    fn binds_like_call_site(&mut self, span: Self::Span) -> bool {
+        // Local bindings are resolved with `macro_rules` hygiene, which
+        // ignores the transparent marks of call-site spans.
+        span.ctxt().normalize_to_macro_rules() == self.call_site.ctxt().normalize_to_macro_rules()
+    }
+
+    fn is_dummy(&mut self, span: Self::Span) -> bool {
+        span.is_dummy()
+    }
+
+    fn to_portable(&mut self, span: Self::Span) -> PortableSpan {
+        let source_map = self.sess().source_map();
+        let lo = source_map.lookup_char_pos(span.lo());
+        let hi = source_map.lookup_char_pos(span.hi());
+        let file_path = match &lo.file.name {
+            FileName::Real(name) if !span.is_dummy() => {
+                name.local_path_if_available().to_string_lossy().into_owned()
+            }
+            _ => String::new(),
+        };
+        PortableSpan {
+            file_path,
+            start: LineColumn { line: lo.line, column: lo.col.to_usize() + 1 },
+            end: LineColumn { line: hi.line, column: hi.col.to_usize() + 1 },
+        }
+    }
+
+    fn is_included(&mut self, span: Self::Span) -> bool {
+        let mut ctxt = span.ctxt();
+        loop {
+            let expn_data = ctxt.outer_expn_data();
+            if expn_data.is_root() {
+                break;
+            }
+            if expn_data.kind == ExpnKind::Macro(MacroKind::Bang, sym::include) {
+                return true;
+            }
+            ctxt = expn_data.call_site.ctxt();
+        }
+        // Tokens parsed by `include!` keep the root context, so they are
+        // recognized by their file instead, which differs from the file of
+        // the module the macro is expanded in.
+        let span = span.source_callsite();
+        if span.is_dummy() {
+            return false;
+        }
+        let file = self.sess().source_map().lookup_source_file(span.lo());
+        let FileName::Real(name) = &file.name else {
+            return false;
+        };
+        match (name.local_path(), self.ecx.current_expansion.module.file_path_stack.last()) {
+            (Some(path), Some(module_path)) => path != module_path.as_path(),
+            _ => false,
+        }
+    }
 }
 
 impl server::Symbol for Rustc<'_, '_> {
@@ -802,6 +1798,10 @@ fn normalize_and_validate_ident(&mut self, string: &str) -> Result<Self::Symbol,
         let sym = nfc_normalize(string);
         if rustc_lexer::is_ident(sym.as_str()) { Ok(sym) } else { Err(()) }
     }
+
+    fn is_reserved(&mut self, string: &str, span: Self::Span) -> bool {
+        symbol::Ident::new(Symbol::intern(string), span).is_reserved()
+    }
 }
 
 impl server::Server for Rustc<'_, '_> {
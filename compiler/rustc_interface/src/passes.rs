@@ -195,6 +195,7 @@ fn configure_and_expand(
             should_test: sess.is_test_crate(),
             span_debug: sess.opts.unstable_opts.span_debug,
             proc_macro_backtrace: sess.opts.unstable_opts.proc_macro_backtrace,
+            is_proc_macro_crate: tcx.crate_types().contains(&CrateType::ProcMacro),
         };
 
         let lint_store = LintStoreExpandImpl(lint_store);
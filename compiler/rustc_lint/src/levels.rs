@@ -213,6 +213,7 @@ pub trait LintLevelsProvider {
     fn insert(&mut self, id: LintId, lvl: LevelAndSource);
     fn get_lint_level(&self, lint: &'static Lint, sess: &Session) -> LevelAndSource;
     fn push_expectation(&mut self, _id: LintExpectationId, _expectation: LintExpectation) {}
+    fn map_expectation_id(&mut self, _id: LintExpectationId) {}
 }
 
 impl LintLevelsProvider for TopDown {
@@ -275,6 +276,10 @@ fn get_lint_level(&self, lint: &'static Lint, _: &Session) -> LevelAndSource {
         self.specs.lint_level_id_at_node(self.tcx, LintId::of(lint), self.cur)
     }
     fn push_expectation(&mut self, id: LintExpectationId, expectation: LintExpectation) {
+        self.map_expectation_id(id);
+        self.expectations.push((id.normalize(), expectation));
+    }
+    fn map_expectation_id(&mut self, id: LintExpectationId) {
         let LintExpectationId::Stable { attr_id: Some(attr_id), hir_id, attr_index, .. } = id
         else {
             bug!("unstable expectation id should already be mapped")
@@ -287,8 +292,6 @@ fn push_expectation(&mut self, id: LintExpectationId, expectation: LintExpectati
             lint_index: None,
             attr_id: None,
         });
-
-        self.expectations.push((id.normalize(), expectation));
     }
 }
 
@@ -952,6 +955,13 @@ fn add(&mut self, attrs: &[ast::Attribute], is_crate_node: bool, source_hir_id:
                                 // exist in the tool or the code was not compiled with the tool and
                                 // therefore the lint was never added to the `LintStore`. To detect
                                 // this is the responsibility of the lint tool.
+                                //
+                                // Proc macros can still emit such lints, which need the stable id
+                                // to fulfill an expectation. It isn't pushed, since only the tool
+                                // knows whether the lint is checked at all.
+                                if let Level::Expect(expect_id) = level {
+                                    self.provider.map_expectation_id(expect_id);
+                                }
                             }
                         }
                     }
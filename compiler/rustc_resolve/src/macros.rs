@@ -473,6 +473,10 @@ fn get_proc_macro_quoted_span(&self, krate: CrateNum, id: usize) -> Span {
         self.cstore().get_proc_macro_quoted_span_untracked(krate, id, self.tcx.sess)
     }
 
+    fn crate_name(&self, krate: CrateNum) -> Symbol {
+        self.tcx.crate_name(krate)
+    }
+
     fn declare_proc_macro(&mut self, id: NodeId) {
         self.proc_macros.push(id)
     }
@@ -8,7 +8,8 @@
 
 #![deny(unsafe_code)]
 
-use crate::{Delimiter, Level, Spacing};
+use crate::{CompilerChannel, Delimiter, Level, LineColumn, PortableSpan, Spacing};
+use std::cmp::Ordering;
 use std::fmt;
 use std::hash::Hash;
 use std::marker;
@@ -60,6 +61,21 @@ macro_rules! with_api {
                 fn track_path(path: &str);
                 fn literal_from_str(s: &str) -> Result<Literal<$S::Span, $S::Symbol>, ()>;
                 fn emit_diagnostic(diagnostic: Diagnostic<$S::Span>);
+                fn emit_diagnostics(diagnostics: Vec<Diagnostic<$S::Span>>);
+                fn derive_siblings() -> Vec<String>;
+                fn invocation_args_span() -> $S::Span;
+                fn validate_literal(literal: Literal<$S::Span, $S::Symbol>) -> Result<(), String>;
+                fn unescape_literal(literal: Literal<$S::Span, $S::Symbol>) -> Option<Vec<u8>>;
+                fn is_proc_macro_crate() -> bool;
+                fn feature_enabled(feature: &str) -> bool;
+                fn macro_crate_name() -> String;
+                fn input_token_count() -> usize;
+                fn enclosing_impl_span() -> Option<$S::Span>;
+                fn enclosing_impl_self_ty() -> Option<$S::TokenStream>;
+                fn compiler_channel() -> CompilerChannel;
+                fn target_pointer_width() -> u32;
+                fn emit_diagnostic_deduplicated(diagnostic: Diagnostic<$S::Span>);
+                fn out_dir() -> Option<String>;
             },
             TokenStream {
                 fn drop($self: $S::TokenStream);
@@ -67,7 +83,10 @@ macro_rules! with_api {
                 fn is_empty($self: &$S::TokenStream) -> bool;
                 fn expand_expr($self: &$S::TokenStream) -> Result<$S::TokenStream, ()>;
                 fn from_str(src: &str) -> $S::TokenStream;
+                fn type_path(path: &str) -> Result<$S::TokenStream, String>;
                 fn to_string($self: &$S::TokenStream) -> String;
+                fn to_string_formatted($self: &$S::TokenStream, minify: bool) -> String;
+                fn contains_ident($self: &$S::TokenStream, name: &str, is_raw: bool) -> bool;
                 fn from_token_tree(
                     tree: TokenTree<$S::TokenStream, $S::Span, $S::Symbol>,
                 ) -> $S::TokenStream;
@@ -82,6 +101,20 @@ fn concat_streams(
                 fn into_trees(
                     $self: $S::TokenStream
                 ) -> Vec<TokenTree<$S::TokenStream, $S::Span, $S::Symbol>>;
+                fn unwrap_single($self: $S::TokenStream) -> $S::TokenStream;
+                fn diff(
+                    old: Option<$S::TokenStream>,
+                    new: Option<$S::TokenStream>,
+                ) -> Vec<TokenEdit>;
+                fn repeat(
+                    template: Option<$S::TokenStream>,
+                    count: usize,
+                    separator: Option<TokenTree<$S::TokenStream, $S::Span, $S::Symbol>>,
+                ) -> $S::TokenStream;
+                fn first_kind($self: &$S::TokenStream) -> Option<u8>;
+                fn validate_as_item($self: &$S::TokenStream) -> Result<(), String>;
+                fn validate_as_expr($self: &$S::TokenStream) -> Result<(), String>;
+                fn nth_span($self: &$S::TokenStream, n: usize) -> Option<$S::Span>;
             },
             SourceFile {
                 fn drop($self: $S::SourceFile);
@@ -89,6 +122,10 @@ fn into_trees(
                 fn eq($self: &$S::SourceFile, other: &$S::SourceFile) -> bool;
                 fn path($self: &$S::SourceFile) -> String;
                 fn is_real($self: &$S::SourceFile) -> bool;
+                fn source_file_at(pos: u32) -> Option<$S::SourceFile>;
+                fn register_virtual(name: &str, contents: String) -> $S::SourceFile;
+                fn span($self: &$S::SourceFile, range: Range<usize>) -> Option<$S::Span>;
+                fn path_cmp($self: &$S::SourceFile, other: &$S::SourceFile) -> Ordering;
             },
             Span {
                 fn debug($self: $S::Span) -> String;
@@ -106,9 +143,27 @@ fn into_trees(
                 fn source_text($self: $S::Span) -> Option<String>;
                 fn save_span($self: $S::Span) -> usize;
                 fn recover_proc_macro_span(id: usize) -> $S::Span;
+                fn is_generated($self: $S::Span) -> bool;
+                fn expansion_name($self: $S::Span) -> Option<String>;
+                fn truncate($self: $S::Span, max_bytes: usize) -> $S::Span;
+                fn contains($self: $S::Span, other: $S::Span) -> bool;
+                fn with_source_text($self: $S::Span, text: &str) -> $S::Span;
+                fn locations(spans: Vec<$S::Span>) -> Vec<Range<LineColumn>>;
+                fn caller_location($self: $S::Span) -> CallerLocation;
+                fn source_cmp($self: $S::Span, other: $S::Span) -> Ordering;
+                fn line_text($self: $S::Span) -> Option<String>;
+                fn point($self: $S::Span) -> $S::Span;
+                fn extend_to_next_token($self: $S::Span) -> $S::Span;
+                fn to_end_of_line($self: $S::Span) -> $S::Span;
+                fn same_expansion($self: $S::Span, other: $S::Span) -> bool;
+                fn binds_like_call_site($self: $S::Span) -> bool;
+                fn is_dummy($self: $S::Span) -> bool;
+                fn to_portable($self: $S::Span) -> PortableSpan;
+                fn is_included($self: $S::Span) -> bool;
             },
             Symbol {
                 fn normalize_and_validate_ident(string: &str) -> Result<$S::Symbol, ()>;
+                fn is_reserved(string: &str, span: $S::Span) -> bool;
             },
         }
     };
@@ -296,11 +351,18 @@ fn unmark(self) -> Self::Unmarked {
     &'_ str,
     String,
     u8,
+    u32,
     usize,
     Delimiter,
     LitKind,
     Level,
+    LineColumn,
+    Ordering,
     Spacing,
+    TokenEdit,
+    CallerLocation,
+    CompilerChannel,
+    PortableSpan,
 }
 
 rpc_encode_decode!(
@@ -325,6 +387,21 @@ enum Spacing {
         Joint,
     }
 );
+rpc_encode_decode!(
+    enum Ordering {
+        Less,
+        Equal,
+        Greater,
+    }
+);
+rpc_encode_decode!(
+    enum CompilerChannel {
+        Stable,
+        Beta,
+        Nightly,
+        Dev,
+    }
+);
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum LitKind {
@@ -451,9 +528,12 @@ pub struct Group<TokenStream, Span> {
     pub delimiter: Delimiter,
     pub stream: Option<TokenStream>,
     pub span: DelimSpan<Span>,
+    /// Whether this is a `Delimiter::None` group created by the compiler
+    /// around an interpolated `macro_rules!` fragment.
+    pub interpolated: bool,
 }
 
-compound_traits!(struct Group<TokenStream, Span> { delimiter, stream, span });
+compound_traits!(struct Group<TokenStream, Span> { delimiter, stream, span, interpolated });
 
 #[derive(Clone)]
 pub struct Punct<Span> {
@@ -506,10 +586,46 @@ pub struct Diagnostic<Span> {
     pub message: String,
     pub spans: Vec<Span>,
     pub children: Vec<Diagnostic<Span>>,
+    pub tool_lint: Option<String>,
+    pub future_incompatible: bool,
 }
 
 compound_traits!(
-    struct Diagnostic<Span> { level, message, spans, children }
+    struct Diagnostic<Span> { level, message, spans, children, tool_lint, future_incompatible }
+);
+
+/// A token inserted into or deleted from a token stream, as part of the edit
+/// script computed by the `diff` method.
+#[derive(Clone, Debug)]
+pub struct TokenEdit {
+    pub insert: bool,
+    pub index: usize,
+    pub token: String,
+}
+
+rpc_encode_decode!(
+    struct TokenEdit { insert, index, token }
+);
+
+rpc_encode_decode!(
+    struct LineColumn { line, column }
+);
+
+/// The location of a span's start, in the form used by
+/// `core::panic::Location`.
+#[derive(Clone, Debug)]
+pub struct CallerLocation {
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+rpc_encode_decode!(
+    struct CallerLocation { file, line, column }
+);
+
+rpc_encode_decode!(
+    struct PortableSpan { file_path, start, end }
 );
 
 /// Globals provided alongside the initial inputs for a macro expansion.
@@ -32,12 +32,18 @@ pub(crate) fn new(string: &str) -> Self {
     ///
     /// Validates and normalizes before converting it to a symbol.
     pub(crate) fn new_ident(string: &str, is_raw: bool) -> Self {
+        Self::try_new_ident(string, is_raw).unwrap_or_else(|msg| panic!("{}", msg))
+    }
+
+    /// Same as `new_ident`, but returns the error message instead of
+    /// panicking if `string` is not a valid identifier.
+    pub(crate) fn try_new_ident(string: &str, is_raw: bool) -> Result<Self, String> {
         // Fast-path: check if this is a valid ASCII identifier
         if Self::is_valid_ascii_ident(string.as_bytes()) {
             if is_raw && !Self::can_be_raw(string) {
-                panic!("`{}` cannot be a raw identifier", string);
+                return Err(format!("`{}` cannot be a raw identifier", string));
             }
-            return Self::new(string);
+            return Ok(Self::new(string));
         }
 
         // Slow-path: If the string is already ASCII we're done, otherwise ask
@@ -49,7 +55,12 @@ pub(crate) fn new_ident(string: &str, is_raw: bool) -> Self {
         } else {
             client::Symbol::normalize_and_validate_ident(string)
         }
-        .unwrap_or_else(|_| panic!("`{:?}` is not a valid identifier", string))
+        .map_err(|_| format!("`{:?}` is not a valid identifier", string))
+    }
+
+    /// Returns the index of the symbol in the interner.
+    pub(crate) fn as_u32(self) -> u32 {
+        self.0.get()
     }
 
     /// Run a callback with the symbol's string value.
@@ -77,7 +88,7 @@ fn is_valid_ascii_ident(bytes: &[u8]) -> bool {
     }
 
     // Mimics the behaviour of `Symbol::can_be_raw` from `rustc_span`
-    fn can_be_raw(string: &str) -> bool {
+    pub(crate) fn can_be_raw(string: &str) -> bool {
         match string {
             "_" | "super" | "self" | "Self" | "crate" => false,
             _ => true,
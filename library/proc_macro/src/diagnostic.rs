@@ -45,6 +45,9 @@ fn into_spans(self) -> Vec<Span> {
 
 /// A structure representing a diagnostic message and associated children
 /// messages.
+///
+/// A diagnostic is only reported once it is emitted, so one that is dropped
+/// without calling [`Diagnostic::emit`] is discarded silently.
 #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
 #[derive(Clone, Debug)]
 pub struct Diagnostic {
@@ -52,6 +55,8 @@ pub struct Diagnostic {
     message: String,
     spans: Vec<Span>,
     children: Vec<Diagnostic>,
+    tool_lint: Option<String>,
+    future_incompatible: bool,
 }
 
 macro_rules! diagnostic_child_methods {
@@ -97,7 +102,14 @@ impl Diagnostic {
     /// Creates a new diagnostic with the given `level` and `message`.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn new<T: Into<String>>(level: Level, message: T) -> Diagnostic {
-        Diagnostic { level, message: message.into(), spans: vec![], children: vec![] }
+        Diagnostic {
+            level,
+            message: message.into(),
+            spans: vec![],
+            children: vec![],
+            tool_lint: None,
+            future_incompatible: false,
+        }
     }
 
     /// Creates a new diagnostic with the given `level` and `message` pointing to
@@ -108,7 +120,35 @@ pub fn spanned<S, T>(spans: S, level: Level, message: T) -> Diagnostic
         S: MultiSpan,
         T: Into<String>,
     {
-        Diagnostic { level, message: message.into(), spans: spans.into_spans(), children: vec![] }
+        Diagnostic {
+            level,
+            message: message.into(),
+            spans: spans.into_spans(),
+            children: vec![],
+            tool_lint: None,
+            future_incompatible: false,
+        }
+    }
+
+    /// Creates a new diagnostic with the given `level` and `message` pointing
+    /// to `primary`, with a note pointing to the invocation of the running
+    /// macro, `Span::call_site()`, labeled "in this macro invocation".
+    ///
+    /// This shows users which invocation an error in e.g. a generated item
+    /// belongs to. The note is left out if `primary` is the call site itself.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn new_with_callsite_context<T: Into<String>>(
+        level: Level,
+        message: T,
+        primary: Span,
+    ) -> Diagnostic {
+        let call_site = Span::call_site();
+        let diagnostic = Diagnostic::spanned(primary, level, message);
+        if primary.eq(&call_site) {
+            diagnostic
+        } else {
+            diagnostic.span_note(call_site, "in this macro invocation")
+        }
     }
 
     diagnostic_child_methods!(span_error, error, Level::Error);
@@ -116,6 +156,65 @@ pub fn spanned<S, T>(spans: S, level: Level, message: T) -> Diagnostic
     diagnostic_child_methods!(span_note, note, Level::Note);
     diagnostic_child_methods!(span_help, help, Level::Help);
 
+    /// Adds a new child note to `self` that links to `url`, e.g. to point to
+    /// online documentation.
+    ///
+    /// The URL is appended to `message` in angle brackets, the way rustc
+    /// presents links, as in `for more information, see <https://...>`.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn note_with_link<T: Into<String>>(self, message: T, url: &str) -> Diagnostic {
+        let message = message.into();
+        self.note(format!("{message} <{url}>"))
+    }
+
+    /// Marks `self`, usually a warning, as being about something that will
+    /// become an error in a future version of the macro, adding the usual
+    /// explanation for this and `note` as child messages, e.g. to point to
+    /// migration advice.
+    ///
+    /// Like the compiler's future incompatibility lints, the diagnostic is
+    /// also included in the future incompatibility report shown by Cargo,
+    /// so it is seen even if the warning is only emitted for a dependency.
+    /// The report names each entry by its lint, so the diagnostic becomes
+    /// the tool lint `tool::lint`, as with [`Diagnostic::set_tool_lint`].
+    #[unstable(feature = "proc_macro_future_incompatible", issue = "none")]
+    pub fn future_incompatible<T: Into<String>>(
+        mut self,
+        tool: &str,
+        lint: &str,
+        note: T,
+    ) -> Diagnostic {
+        self.set_tool_lint(tool, lint);
+        self.future_incompatible = true;
+        self.warning(
+            "this was previously accepted by the macro but is being phased out; \
+             it will become a hard error in a future release!",
+        )
+        .note(note)
+    }
+
+    /// Adds a group of child messages with the given `level` to `self`: a
+    /// `header` message without a span, followed by one message for each of
+    /// the `items`, pointing to the item's span.
+    ///
+    /// Child messages are rendered in the order they were added, so keeping
+    /// related messages next to each other under a common header makes
+    /// diagnostics with many of them easier to read. The compiler does not
+    /// indent the items relative to the header.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn sub_group<T: Into<String>>(
+        mut self,
+        level: Level,
+        header: T,
+        items: Vec<(Span, String)>,
+    ) -> Diagnostic {
+        self.children.push(Diagnostic::new(level, header));
+        for (span, message) in items {
+            self.children.push(Diagnostic::spanned(span, level, message));
+        }
+        self
+    }
+
     /// Returns the diagnostic `level` for `self`.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn level(&self) -> Level {
@@ -135,6 +234,10 @@ pub fn message(&self) -> &str {
     }
 
     /// Sets the message in `self` to `message`.
+    ///
+    /// This replaces the primary message given to `Diagnostic::new`, and can
+    /// be called at any point before the diagnostic is emitted, e.g. to
+    /// write a more specific headline once all children have been added.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn set_message<T: Into<String>>(&mut self, message: T) {
         self.message = message.into();
@@ -152,6 +255,24 @@ pub fn set_spans<S: MultiSpan>(&mut self, spans: S) {
         self.spans = spans.into_spans();
     }
 
+    /// Marks `self` as belonging to the tool lint `tool::lint`, e.g.
+    /// `my_framework::my_lint`, so that its level can be changed like that of
+    /// other lints, e.g. with `#[allow(my_framework::my_lint)]` around the
+    /// macro invocation or with `-D my_framework::my_lint`.
+    ///
+    /// The crate using the macro has to register the tool with
+    /// `#![register_tool(my_framework)]`, as the compiler rejects lint names
+    /// of unknown tools. The lint attributes on the macro invocation and on
+    /// the items, statements and expressions around it apply, regardless of
+    /// the spans of the diagnostic, as do the `warnings` group and
+    /// `--cap-lints`. Without any of these, the diagnostic keeps its level.
+    /// Only error and warning diagnostics are affected; the lint of a child
+    /// diagnostic is ignored.
+    #[unstable(feature = "proc_macro_tool_lint", issue = "none")]
+    pub fn set_tool_lint(&mut self, tool: &str, lint: &str) {
+        self.tool_lint = Some(format!("{tool}::{lint}"));
+    }
+
     /// Returns an iterator over the children diagnostics of `self`.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn children(&self) -> Children<'_> {
@@ -161,15 +282,44 @@ pub fn children(&self) -> Children<'_> {
     /// Emit the diagnostic.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn emit(self) {
-        fn to_internal(diag: Diagnostic) -> crate::bridge::Diagnostic<crate::bridge::client::Span> {
-            crate::bridge::Diagnostic {
-                level: diag.level,
-                message: diag.message,
-                spans: diag.spans.into_iter().map(|s| s.0).collect(),
-                children: diag.children.into_iter().map(to_internal).collect(),
-            }
+        crate::bridge::client::FreeFunctions::emit_diagnostic(to_internal(self));
+    }
+
+    /// Emit the diagnostic, unless a diagnostic with the same spans and
+    /// message was already emitted this way by the running macro.
+    ///
+    /// This keeps macros which inspect their input in several passes from
+    /// reporting the same problem more than once. Only exact duplicates are
+    /// suppressed, diagnostics which differ in any span or in their message
+    /// are all emitted. Diagnostics emitted with [`Diagnostic::emit`] are not
+    /// taken into account.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn emit_deduplicated(self) {
+        crate::bridge::client::FreeFunctions::emit_diagnostic_deduplicated(to_internal(self));
+    }
+
+    /// Emit all of the given diagnostics, in order.
+    ///
+    /// This is equivalent to calling [`Diagnostic::emit`] on each element of
+    /// `diagnostics`, but hands them to the compiler in a single request.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn emit_all(diagnostics: Vec<Diagnostic>) {
+        if diagnostics.is_empty() {
+            return;
         }
+        crate::bridge::client::FreeFunctions::emit_diagnostics(
+            diagnostics.into_iter().map(to_internal).collect(),
+        );
+    }
+}
 
-        crate::bridge::client::FreeFunctions::emit_diagnostic(to_internal(self));
+fn to_internal(diag: Diagnostic) -> crate::bridge::Diagnostic<crate::bridge::client::Span> {
+    crate::bridge::Diagnostic {
+        level: diag.level,
+        message: diag.message,
+        spans: diag.spans.into_iter().map(|s| s.0).collect(),
+        children: diag.children.into_iter().map(to_internal).collect(),
+        tool_lint: diag.tool_lint,
+        future_incompatible: diag.future_incompatible,
     }
 }
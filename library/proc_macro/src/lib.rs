@@ -45,6 +45,7 @@
 #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
 pub use diagnostic::{Diagnostic, Level, MultiSpan};
 
+use std::cmp::Ordering;
 use std::ops::{Range, RangeBounds};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -68,6 +69,112 @@ pub fn is_available() -> bool {
     bridge::client::is_available()
 }
 
+/// Returns the paths of the other derive macros listed in the same
+/// `#[derive(...)]` attribute as the currently running derive, in the order
+/// they were written, e.g. `["Clone", "serde::Serialize"]`.
+///
+/// Only derives from the same attribute are included, not those from other
+/// `#[derive]` attributes on the item. The list is empty when not called from
+/// a derive macro. No guarantee is made about which siblings have already
+/// been expanded.
+#[unstable(feature = "proc_macro_derive_siblings", issue = "none")]
+pub fn derive_siblings() -> Vec<String> {
+    bridge::client::FreeFunctions::derive_siblings()
+}
+
+/// Returns whether the crate currently being compiled, i.e. the crate in
+/// which the running macro is expanded, has the `proc-macro` crate type.
+///
+/// This is about the crate using the macro, not the crate defining it, which
+/// always is a `proc-macro` crate. It lets helper crates shared between proc
+/// macros and ordinary crates find out in which of those they are expanded.
+#[unstable(feature = "proc_macro_is_proc_macro_crate", issue = "none")]
+pub fn is_proc_macro_crate() -> bool {
+    bridge::client::FreeFunctions::is_proc_macro_crate()
+}
+
+/// Returns whether `feature` is enabled in the crate in which the running
+/// macro is expanded, i.e. named in one of its `#![feature(...)]` attributes.
+///
+/// Any language or library feature can be checked, by the name it is enabled
+/// with, e.g. `"never_type"`. Features enabled through `-Zcrate-attr` are
+/// included. This allows a macro to only emit nightly-only syntax when the
+/// feature it needs is enabled, and to fall back to stable code otherwise.
+#[unstable(feature = "proc_macro_feature_enabled", issue = "none")]
+pub fn feature_enabled(feature: &str) -> bool {
+    bridge::client::FreeFunctions::feature_enabled(feature)
+}
+
+/// Returns the number of tokens passed to the running macro, counting the
+/// tokens of both the arguments and the item for attribute macros.
+///
+/// Tokens nested in groups are counted as well, with each group counting
+/// as one token in addition. This lets macros reject excessively large
+/// inputs with a clear error before doing expensive processing.
+#[unstable(feature = "proc_macro_input_token_count", issue = "none")]
+pub fn input_token_count() -> usize {
+    bridge::client::FreeFunctions::input_token_count()
+}
+
+/// The release channel of a compiler, as returned by [`compiler_channel`].
+#[unstable(feature = "proc_macro_compiler_channel", issue = "none")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CompilerChannel {
+    /// A stable release.
+    Stable,
+    /// A beta release.
+    Beta,
+    /// A nightly release.
+    Nightly,
+    /// A local build of the compiler, or one from an unknown channel.
+    Dev,
+}
+
+/// Returns the release channel of the compiler running the macro.
+///
+/// This lets a macro emit code relying on nightly-only functionality of the
+/// compiler when it is available. To check whether the crate enables a
+/// specific feature, use [`feature_enabled`] instead. Note that a macro whose
+/// expansion depends on the channel behaves differently between toolchains,
+/// which can make builds less reproducible.
+#[unstable(feature = "proc_macro_compiler_channel", issue = "none")]
+pub fn compiler_channel() -> CompilerChannel {
+    bridge::client::FreeFunctions::compiler_channel()
+}
+
+/// Returns the directory the compiler writes its output to, as set with
+/// `--out-dir` or derived from the path given with `-o`, which takes
+/// precedence. This is `.` if the path given with `-o` has no directory part.
+/// Returns `None` if neither is given, or if the path is not valid UTF-8.
+///
+/// Writing files from a macro is discouraged: the compiler does not know
+/// about them, so they are not cleaned up, and tools like rust-analyzer
+/// expand macros without producing any output at all. Macros that need to
+/// generate files should usually leave this to a build script, which gets a
+/// dedicated `OUT_DIR`.
+#[unstable(feature = "proc_macro_out_dir", issue = "none")]
+pub fn out_dir() -> Option<String> {
+    bridge::client::FreeFunctions::out_dir()
+}
+
+/// If the running macro is invoked inside of an `impl` block, returns the
+/// span of that block and the tokens of its self type, e.g. `Vec<T>` for
+/// `impl<T> Trait for Vec<T>`. Returns `None` otherwise.
+///
+/// This lets attribute macros on methods refer to the self type without
+/// the user restating it. Macros invoked in items nested in functions of the
+/// block get the innermost `impl` block. As macros are expanded from the
+/// outside in, this is `None` for a macro applied to the `impl` block itself,
+/// and the block is only seen once it is no longer produced by a macro. The
+/// tokens of the self type are reconstructed from the parsed type, so they
+/// may be formatted differently than written.
+#[unstable(feature = "proc_macro_enclosing_impl", issue = "none")]
+pub fn enclosing_impl() -> Option<(Span, TokenStream)> {
+    let span = bridge::client::FreeFunctions::enclosing_impl_span()?;
+    let self_ty = bridge::client::FreeFunctions::enclosing_impl_self_ty()?;
+    Some((Span(span), TokenStream(Some(self_ty))))
+}
+
 /// The main type provided by this crate, representing an abstract stream of
 /// tokens, or, more specifically, a sequence of token trees.
 /// The type provide interfaces for iterating over those token trees and, conversely,
@@ -128,6 +235,92 @@ impl !Send for ExpandError {}
 #[unstable(feature = "proc_macro_expand", issue = "90765")]
 impl !Sync for ExpandError {}
 
+/// A single change in the edit script returned by [`TokenStream::diff`].
+///
+/// Tokens are compared by their textual form, ignoring spans. Groups are
+/// represented by separate tokens for their opening and closing delimiters,
+/// except for groups with `Delimiter::None` which only contribute their
+/// contents.
+#[unstable(feature = "proc_macro_diff", issue = "none")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenDiff {
+    /// A token of the old stream which is not in the new one.
+    Delete {
+        /// The position of the token in the old stream.
+        index: usize,
+        /// The textual form of the token.
+        token: String,
+    },
+    /// A token of the new stream which is not in the old one.
+    Insert {
+        /// The position of the token in the new stream.
+        index: usize,
+        /// The textual form of the token.
+        token: String,
+    },
+}
+
+/// The kind of a token tree, i.e. the variant of `TokenTree` it would be
+/// represented by, as returned by `TokenStream::first_kind`.
+///
+/// The discriminants are fixed and can be relied upon, e.g. for building jump
+/// tables from `kind as u8`.
+#[unstable(feature = "proc_macro_first_kind", issue = "none")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum TokenTreeKind {
+    /// A `TokenTree::Group`.
+    Group = 0,
+    /// A `TokenTree::Ident`.
+    Ident = 1,
+    /// A `TokenTree::Punct`.
+    Punct = 2,
+    /// A `TokenTree::Literal`.
+    Literal = 3,
+}
+
+/// The kind of a generic parameter, as part of a [`GenericParamSpec`].
+#[unstable(feature = "proc_macro_generic_params", issue = "none")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GenericParamKind {
+    /// A lifetime parameter, `'a`.
+    Lifetime,
+    /// A type parameter, `T`.
+    Type,
+    /// A const parameter, `const N: usize`.
+    Const,
+}
+
+/// A generic parameter, as taken by [`TokenStream::generic_params`].
+#[unstable(feature = "proc_macro_generic_params", issue = "none")]
+#[derive(Clone, Debug)]
+pub struct GenericParamSpec {
+    /// The kind of the parameter.
+    #[unstable(feature = "proc_macro_generic_params", issue = "none")]
+    pub kind: GenericParamKind,
+    /// The name of the parameter, without the `'` of a lifetime.
+    #[unstable(feature = "proc_macro_generic_params", issue = "none")]
+    pub name: Ident,
+    /// The bounds of a lifetime or type parameter, e.g. `Clone + 'a`, or the
+    /// type of a const parameter.
+    #[unstable(feature = "proc_macro_generic_params", issue = "none")]
+    pub bounds: Option<TokenStream>,
+    /// The default of a type or const parameter, e.g. `String` or `3`.
+    #[unstable(feature = "proc_macro_generic_params", issue = "none")]
+    pub default: Option<TokenStream>,
+}
+
+/// One step of a field access chain, as taken by
+/// [`TokenStream::field_access`].
+#[unstable(feature = "proc_macro_field_access", issue = "none")]
+#[derive(Clone, Debug)]
+pub enum FieldAccess {
+    /// A named field, e.g. `.name`.
+    Named(Ident),
+    /// A field of a tuple or tuple struct, e.g. `.0`.
+    Indexed(usize),
+}
+
 impl TokenStream {
     /// Returns an empty `TokenStream` containing no token trees.
     #[stable(feature = "proc_macro_lib2", since = "1.29.0")]
@@ -159,6 +352,806 @@ pub fn expand_expr(&self) -> Result<TokenStream, ExpandError> {
             Err(_) => Err(ExpandError),
         }
     }
+
+    /// Creates a token stream for the lifetime `'name`, consisting of a joint
+    /// `'` punctuation character followed by the identifier `name`, which is
+    /// how lifetimes are represented in token streams.
+    ///
+    /// `name` may be given with or without its leading `'`. Both tokens get
+    /// the given `span`. Returns an error if `name` is not an identifier, or
+    /// is a keyword other than `static` and `_`.
+    #[unstable(feature = "proc_macro_lifetime", issue = "none")]
+    pub fn lifetime(name: &str, span: Span) -> Result<TokenStream, String> {
+        let name = name.strip_prefix('\'').unwrap_or(name);
+        let sym = bridge::client::Symbol::try_new_ident(name, false)?;
+        if !matches!(name, "static" | "_") && bridge::client::Symbol::is_reserved(name, span.0) {
+            return Err(format!("lifetimes cannot use keyword names: `'{name}`"));
+        }
+        let apostrophe = Punct(bridge::Punct { ch: b'\'', joint: true, span: span.0 });
+        let ident = Ident(bridge::Ident { sym, is_raw: false, span: span.0 });
+        Ok([TokenTree::Punct(apostrophe), TokenTree::Ident(ident)].into_iter().collect())
+    }
+
+    /// Parses `path` as a type path, e.g. `std::collections::HashMap<K, V>`,
+    /// and returns its tokens, which get `Span::call_site()` spans.
+    ///
+    /// Unlike parsing with `str::parse`, the whole string must form a single
+    /// path, and a description of the problem is returned if it does not.
+    #[unstable(feature = "proc_macro_type_path", issue = "none")]
+    pub fn type_path(path: &str) -> Result<TokenStream, String> {
+        bridge::client::TokenStream::type_path(path).map(|stream| TokenStream(Some(stream)))
+    }
+
+    /// Parses `fmt` as Rust source, with each `{}` placeholder in it replaced
+    /// by the corresponding stream of `args`, in order. As in format strings,
+    /// `{{` and `}}` stand for literal braces, so an empty block is written as
+    /// `{{}}`.
+    ///
+    /// The arguments are spliced in as tokens, so placeholders cannot be used
+    /// inside of literals or comments. An error is returned for unmatched
+    /// braces, and if the number of placeholders differs from the number of
+    /// arguments.
+    #[unstable(feature = "proc_macro_template", issue = "none")]
+    pub fn template(fmt: &str, args: &[TokenStream]) -> Result<TokenStream, String> {
+        let mut src = String::with_capacity(fmt.len());
+        let mut placeholders = 0;
+        let mut chars = fmt.chars();
+        while let Some(ch) = chars.next() {
+            match (ch, chars.clone().next()) {
+                ('{', Some('{')) | ('}', Some('}')) => {
+                    chars.next();
+                    src.push(ch);
+                }
+                ('{', Some('}')) => {
+                    chars.next();
+                    // Keep the placeholder separate from surrounding tokens.
+                    src.push_str(&format!(" {TEMPLATE_ARG_PREFIX}{placeholders} "));
+                    placeholders += 1;
+                }
+                ('{' | '}', _) => return Err(format!("unmatched `{ch}` in template")),
+                _ => src.push(ch),
+            }
+        }
+        if placeholders != args.len() {
+            return Err(format!(
+                "template has {placeholders} placeholders, but {} arguments were given",
+                args.len()
+            ));
+        }
+        let stream = TokenStream::from_str(&src).map_err(|err| err.to_string())?;
+        Ok(splice_template_args(stream, args))
+    }
+
+    /// Creates the absolute path `::krate::segments...` into the crate which
+    /// defines the running macro, where `krate` is the name that crate was
+    /// compiled with. All tokens get the given span.
+    ///
+    /// Returns an error if a segment is not a valid identifier. Note that the
+    /// path does not take into account renaming of the crate by its users,
+    /// e.g. through the `package` key in a Cargo manifest.
+    #[unstable(feature = "proc_macro_crate_path", issue = "none")]
+    pub fn crate_path(segments: &[&str], span: Span) -> Result<TokenStream, String> {
+        let krate = bridge::client::FreeFunctions::macro_crate_name();
+        let colon =
+            |joint| TokenTree::Punct(Punct(bridge::Punct { ch: b':', joint, span: span.0 }));
+        let mut trees = Vec::with_capacity((segments.len() + 1) * 3);
+        for segment in std::iter::once(&*krate).chain(segments.iter().copied()) {
+            let sym = bridge::client::Symbol::try_new_ident(segment, false)?;
+            let ident = Ident(bridge::Ident { sym, is_raw: false, span: span.0 });
+            trees.extend([colon(true), colon(false), TokenTree::Ident(ident)]);
+        }
+        Ok(trees.into_iter().collect())
+    }
+
+    /// If this stream consists of a single group with `Delimiter::None`,
+    /// returns the contents of that group, repeating this for as long as the
+    /// result is again such a group. Otherwise the stream is returned as is.
+    ///
+    /// The compiler wraps interpolated fragments like `$e:expr` from
+    /// `macro_rules!` in such invisible groups, so this peels them off. A few
+    /// fragments passed to attribute and derive macros are already unwrapped
+    /// by the compiler for backwards compatibility; this method applies the
+    /// same unwrapping to all of them.
+    #[unstable(feature = "proc_macro_unwrap_single", issue = "none")]
+    pub fn unwrap_single(self) -> TokenStream {
+        TokenStream(self.0.map(bridge::client::TokenStream::unwrap_single))
+    }
+
+    /// Wraps this stream in a single group with the given span, delimited by
+    /// parentheses, or by `Delimiter::None` if `invisible` is set.
+    ///
+    /// Splicing an expression into a larger one, as in `#expr * 2`, can change
+    /// its meaning when the expression is e.g. `a + b`. Wrapping it first keeps
+    /// it together. Explicit parentheses work in every context, but may cause
+    /// an `unused_parens` warning in the expanded code; invisible delimiters
+    /// are respected by the compiler's parser as well, but are lost when the
+    /// tokens are converted to a string, so they should be preferred only for
+    /// output that is parsed directly.
+    #[unstable(feature = "proc_macro_parenthesize", issue = "none")]
+    pub fn parenthesize(self, invisible: bool, span: Span) -> TokenStream {
+        let delimiter = if invisible { Delimiter::None } else { Delimiter::Parenthesis };
+        let mut group = Group::new(delimiter, self);
+        group.set_span(span);
+        TokenStream::from(TokenTree::Group(group))
+    }
+
+    /// If this stream consists of a single group, returns its delimiter and
+    /// the stream inside of it, e.g. `Delimiter::Parenthesis` and `a, b` for
+    /// `(a, b)`. Returns `None` for any other stream.
+    ///
+    /// Groups with `Delimiter::None` are returned like any other group, use
+    /// `unwrap_single` to look through them first.
+    #[unstable(feature = "proc_macro_unwrap_group", issue = "none")]
+    pub fn unwrap_group(self) -> Option<(Delimiter, TokenStream)> {
+        let mut trees = self.into_iter();
+        match (trees.next(), trees.next()) {
+            (Some(TokenTree::Group(group)), None) => Some((group.delimiter(), group.stream())),
+            _ => None,
+        }
+    }
+
+    /// Creates a stream of `idents` separated by `separator` punctuation with
+    /// the given span, e.g. `a, b, c` for a `,` separator. No separator is
+    /// added after the last identifier, and an empty slice gives an empty
+    /// stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `separator` is not a valid punctuation character, in the
+    /// same cases as `Punct::new`.
+    #[unstable(feature = "proc_macro_join_idents", issue = "none")]
+    pub fn join_idents(idents: &[Ident], separator: char, span: Span) -> TokenStream {
+        let mut punct = Punct::new(separator, Spacing::Alone);
+        punct.set_span(span);
+        let mut trees = Vec::with_capacity(idents.len() * 2);
+        for (i, ident) in idents.iter().enumerate() {
+            if i != 0 {
+                trees.push(TokenTree::Punct(punct.clone()));
+            }
+            trees.push(TokenTree::Ident(ident.clone()));
+        }
+        trees.into_iter().collect()
+    }
+
+    /// Creates a stream of `Punct` tokens with the given characters and
+    /// spacings, all with the given span, e.g. the operator `<=>` from
+    /// `[('<', Spacing::Joint), ('=', Spacing::Joint), ('>', Spacing::Alone)]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the characters is not a valid punctuation character,
+    /// in the same cases as `Punct::new`.
+    #[unstable(feature = "proc_macro_puncts", issue = "none")]
+    pub fn puncts(spec: &[(char, Spacing)], span: Span) -> TokenStream {
+        spec.iter()
+            .map(|&(ch, spacing)| {
+                let mut punct = Punct::new(ch, spacing);
+                punct.set_span(span);
+                TokenTree::Punct(punct)
+            })
+            .collect()
+    }
+
+    /// Creates the expression `match scrutinee { pattern => { body }, ... }`
+    /// from the given arms, with all tokens added here getting the given
+    /// span. Every body is wrapped in braces, so it may be any expression or
+    /// a sequence of statements.
+    ///
+    /// Each body is checked to parse, and then the whole expression, which
+    /// covers the scrutinee and the patterns. Returns the parser's error
+    /// message otherwise, prefixed with the index of the arm if its body is
+    /// malformed.
+    #[unstable(feature = "proc_macro_match_expr", issue = "none")]
+    pub fn match_expr(
+        scrutinee: TokenStream,
+        arms: Vec<(TokenStream, TokenStream)>,
+        span: Span,
+    ) -> Result<TokenStream, String> {
+        let punct = |ch, spacing| {
+            let mut punct = Punct::new(ch, spacing);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+        let group = |delimiter, stream| {
+            let mut group = Group::new(delimiter, stream);
+            group.set_span(span);
+            TokenTree::Group(group)
+        };
+        let mut body = Vec::with_capacity(arms.len() * 4);
+        for (i, (pattern, expr)) in arms.into_iter().enumerate() {
+            let block = group(Delimiter::Brace, expr);
+            TokenStream::from(block.clone())
+                .validate_as_expr()
+                .map_err(|message| format!("arm {i}: {message}"))?;
+            body.extend(pattern);
+            body.extend([punct('=', Spacing::Joint), punct('>', Spacing::Alone)]);
+            body.extend([block, punct(',', Spacing::Alone)]);
+        }
+        let mut tokens = vec![TokenTree::Ident(Ident::new("match", span))];
+        tokens.extend(scrutinee);
+        tokens.push(group(Delimiter::Brace, body.into_iter().collect()));
+        let expr: TokenStream = tokens.into_iter().collect();
+        expr.validate_as_expr()?;
+        Ok(expr)
+    }
+
+    /// Creates the closure expression `|param, ...| body`, or `move |...| body`
+    /// if `move_kw` is set, with all tokens added here getting the given span.
+    /// Each parameter is a pattern, optionally with a type ascription like
+    /// `x: u32`. The body is wrapped in braces unless it already is a single
+    /// brace-delimited group, so it may be any expression or a sequence of
+    /// statements.
+    ///
+    /// Returns the parser's error message if the result does not parse as an
+    /// expression, e.g. because a parameter is not a valid pattern.
+    #[unstable(feature = "proc_macro_closure", issue = "none")]
+    pub fn closure(
+        params: Vec<TokenStream>,
+        body: TokenStream,
+        move_kw: bool,
+        span: Span,
+    ) -> Result<TokenStream, String> {
+        let punct = |ch| {
+            let mut punct = Punct::new(ch, Spacing::Alone);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+        let mut tokens = Vec::new();
+        if move_kw {
+            tokens.push(TokenTree::Ident(Ident::new("move", span)));
+        }
+        tokens.push(punct('|'));
+        for (i, param) in params.into_iter().enumerate() {
+            if i > 0 {
+                tokens.push(punct(','));
+            }
+            tokens.extend(param);
+        }
+        tokens.push(punct('|'));
+        let mut trees = body.clone().into_iter();
+        match (trees.next(), trees.next()) {
+            (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Brace => {
+                tokens.push(TokenTree::Group(group))
+            }
+            _ => {
+                let mut group = Group::new(Delimiter::Brace, body);
+                group.set_span(span);
+                tokens.push(TokenTree::Group(group));
+            }
+        }
+        let closure: TokenStream = tokens.into_iter().collect();
+        closure.validate_as_expr()?;
+        Ok(closure)
+    }
+
+    /// Creates the expression `if cond { then_block } else { else_block }`,
+    /// leaving out the `else` branch if `else_block` is `None`, with all
+    /// tokens added here getting the given span. The blocks are wrapped in
+    /// braces unless they already are a brace-delimited group. An
+    /// `else_block` that is another `if` expression is not wrapped either, so
+    /// `else if` chains can be built by nesting calls.
+    ///
+    /// The condition may also be a `let` binding like `let Some(x) = y`.
+    /// Returns the parser's error message if the result does not parse as an
+    /// expression.
+    #[unstable(feature = "proc_macro_if_expr", issue = "none")]
+    pub fn if_expr(
+        cond: TokenStream,
+        then_block: TokenStream,
+        else_block: Option<TokenStream>,
+        span: Span,
+    ) -> Result<TokenStream, String> {
+        let block = |stream: TokenStream| {
+            let mut trees = stream.clone().into_iter();
+            match (trees.next(), trees.next()) {
+                (Some(TokenTree::Group(group)), None) if group.delimiter() == Delimiter::Brace => {
+                    stream
+                }
+                _ => {
+                    let mut group = Group::new(Delimiter::Brace, stream);
+                    group.set_span(span);
+                    TokenStream::from(TokenTree::Group(group))
+                }
+            }
+        };
+        let mut tokens = vec![TokenTree::Ident(Ident::new("if", span))];
+        tokens.extend(cond);
+        tokens.extend(block(then_block));
+        if let Some(else_block) = else_block {
+            tokens.push(TokenTree::Ident(Ident::new("else", span)));
+            match else_block.clone().into_iter().next() {
+                Some(TokenTree::Ident(ident)) if ident.to_string() == "if" => {
+                    tokens.extend(else_block)
+                }
+                _ => tokens.extend(block(else_block)),
+            }
+        }
+        let expr: TokenStream = tokens.into_iter().collect();
+        expr.validate_as_expr()?;
+        Ok(expr)
+    }
+
+    /// Creates a const generic argument from the expression `expr`, e.g. for
+    /// `Foo::<{ N + 1 }>`, wrapping it in braces with the given span where
+    /// they are required. Literals, negated literals like `-1`, single
+    /// identifiers like `N` and blocks are left as they are, as these are the
+    /// only expressions allowed without braces. Longer paths like `consts::N`
+    /// would be taken for a type, so they get braces too.
+    ///
+    /// Returns the parser's error message if the argument does not parse in
+    /// a turbofish, e.g. because `expr` is not an expression.
+    #[unstable(feature = "proc_macro_const_generic_arg", issue = "none")]
+    pub fn const_generic_arg(expr: TokenStream, span: Span) -> Result<TokenStream, String> {
+        let mut trees = expr.clone().into_iter();
+        let needs_braces = match (trees.next(), trees.next(), trees.next()) {
+            (Some(TokenTree::Literal(_)), None, _) => false,
+            (Some(TokenTree::Punct(minus)), Some(TokenTree::Literal(_)), None) => {
+                minus.as_char() != '-'
+            }
+            (Some(TokenTree::Group(group)), None, _) => group.delimiter() != Delimiter::Brace,
+            (Some(TokenTree::Ident(_)), None, _) => false,
+            _ => true,
+        };
+        let arg = if needs_braces {
+            let mut group = Group::new(Delimiter::Brace, expr);
+            group.set_span(span);
+            TokenStream::from(TokenTree::Group(group))
+        } else {
+            expr
+        };
+        let puncts = TokenStream::puncts(&[(':', Spacing::Joint), (':', Spacing::Alone)], span);
+        let mut turbofish = TokenStream::from(TokenTree::Ident(Ident::new("f", span)));
+        turbofish.extend(puncts);
+        let mut args = TokenStream::puncts(&[('<', Spacing::Alone)], span);
+        args.extend(arg.clone());
+        args.extend(TokenStream::puncts(&[('>', Spacing::Alone)], span));
+        turbofish.extend(args);
+        turbofish.validate_as_expr()?;
+        Ok(arg)
+    }
+
+    /// Creates the where clause `where type: bound, ...` from the given pairs
+    /// of a type and its bounds, e.g. `where T: Clone, U: Debug + Send`, with
+    /// all tokens added here getting the given span. No bounds give an empty
+    /// stream, so the result can be spliced into an item unconditionally.
+    ///
+    /// Each predicate is checked to parse, returning the parser's error
+    /// message prefixed with the index of the first malformed one otherwise.
+    #[unstable(feature = "proc_macro_where_clause", issue = "none")]
+    pub fn where_clause(
+        bounds: Vec<(TokenStream, TokenStream)>,
+        span: Span,
+    ) -> Result<TokenStream, String> {
+        if bounds.is_empty() {
+            return Ok(TokenStream::new());
+        }
+        let punct = |ch| {
+            let mut punct = Punct::new(ch, Spacing::Alone);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+        let mut tokens = vec![TokenTree::Ident(Ident::new("where", span))];
+        for (i, (ty, bound)) in bounds.into_iter().enumerate() {
+            let mut predicate: Vec<TokenTree> = ty.into_iter().collect();
+            predicate.push(punct(':'));
+            predicate.extend(bound);
+            // Predicates are only valid in items, so check this one in a
+            // function with an empty body.
+            let item: TokenStream = TokenStream::from_str("fn f() where")
+                .unwrap()
+                .into_iter()
+                .chain(predicate.iter().cloned())
+                .chain([TokenTree::Group(Group::new(Delimiter::Brace, TokenStream::new()))])
+                .collect();
+            item.validate_as_item().map_err(|message| format!("predicate {i}: {message}"))?;
+            if i != 0 {
+                tokens.push(punct(','));
+            }
+            tokens.extend(predicate);
+        }
+        Ok(tokens.into_iter().collect())
+    }
+
+    /// Creates the generic parameter list `<param, ...>` from the given
+    /// parameters in the given order, e.g. `<'a: 'b, T: Clone = String, const
+    /// N: usize = 3>`, with all tokens added here getting the given span. No
+    /// parameters give an empty stream.
+    ///
+    /// The default of a const parameter is wrapped in braces, so that it may
+    /// be any expression. Lifetime parameters have to come first for the
+    /// result to be valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a const parameter does not have a type given in `bounds`.
+    #[unstable(feature = "proc_macro_generic_params", issue = "none")]
+    pub fn generic_params(params: Vec<GenericParamSpec>, span: Span) -> TokenStream {
+        if params.is_empty() {
+            return TokenStream::new();
+        }
+        let punct = |ch, spacing| {
+            let mut punct = Punct::new(ch, spacing);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+        let mut tokens = vec![punct('<', Spacing::Alone)];
+        for (i, param) in params.into_iter().enumerate() {
+            if i != 0 {
+                tokens.push(punct(',', Spacing::Alone));
+            }
+            match param.kind {
+                GenericParamKind::Lifetime => tokens.push(punct('\'', Spacing::Joint)),
+                GenericParamKind::Type => {}
+                GenericParamKind::Const => {
+                    assert!(param.bounds.is_some(), "const parameter `{}` has no type", param.name);
+                    tokens.push(TokenTree::Ident(Ident::new("const", span)));
+                }
+            }
+            tokens.push(TokenTree::Ident(param.name));
+            if let Some(bounds) = param.bounds {
+                tokens.push(punct(':', Spacing::Alone));
+                tokens.extend(bounds);
+            }
+            if let Some(default) = param.default {
+                tokens.push(punct('=', Spacing::Alone));
+                match param.kind {
+                    GenericParamKind::Const => {
+                        let mut group = Group::new(Delimiter::Brace, default);
+                        group.set_span(span);
+                        tokens.push(TokenTree::Group(group));
+                    }
+                    GenericParamKind::Lifetime | GenericParamKind::Type => tokens.extend(default),
+                }
+            }
+        }
+        tokens.push(punct('>', Spacing::Alone));
+        tokens.into_iter().collect()
+    }
+
+    /// Creates the call `<ty as trait_path>::method(args, ...)` of a trait
+    /// method with a fully qualified path, with all tokens added here getting
+    /// the given span, e.g. `<T as Default>::default()`.
+    ///
+    /// Unlike `ty.method(...)` or `Trait::method(...)`, such a call is never
+    /// ambiguous, which makes it robust against methods of the same name from
+    /// other traits in scope.
+    ///
+    /// Returns the parser's error message if `ty` is not a type, `trait_path`
+    /// is not a path to a trait, or one of the arguments is not an expression.
+    #[unstable(feature = "proc_macro_qualified_call", issue = "none")]
+    pub fn qualified_call(
+        ty: TokenStream,
+        trait_path: TokenStream,
+        method: Ident,
+        args: Vec<TokenStream>,
+        span: Span,
+    ) -> Result<TokenStream, String> {
+        let punct = |ch, spacing| {
+            let mut punct = Punct::new(ch, spacing);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+        let mut tokens = vec![punct('<', Spacing::Alone)];
+        tokens.extend(ty);
+        tokens.push(TokenTree::Ident(Ident::new("as", span)));
+        tokens.extend(trait_path);
+        tokens.extend([punct('>', Spacing::Alone), punct(':', Spacing::Joint)]);
+        tokens.extend([punct(':', Spacing::Alone), TokenTree::Ident(method)]);
+        let path: TokenStream = tokens.iter().cloned().collect();
+        path.validate_as_expr().map_err(|message| format!("invalid qualified path: {message}"))?;
+        let mut args_tokens = Vec::new();
+        for (i, arg) in args.into_iter().enumerate() {
+            arg.validate_as_expr().map_err(|message| format!("argument {i}: {message}"))?;
+            if i != 0 {
+                args_tokens.push(punct(',', Spacing::Alone));
+            }
+            args_tokens.extend(arg);
+        }
+        let mut group = Group::new(Delimiter::Parenthesis, args_tokens.into_iter().collect());
+        group.set_span(span);
+        tokens.push(TokenTree::Group(group));
+        Ok(tokens.into_iter().collect())
+    }
+
+    /// Creates the expression accessing the fields in `path` one after the
+    /// other, starting from `base`, e.g. `self.inner.0` for the base `self`
+    /// and the path `[Named(inner), Indexed(0)]`. The tokens added here get
+    /// the given span.
+    ///
+    /// Each tuple index is a separate integer literal, so consecutive ones
+    /// like in `pair.0.1` are not mistaken for a float literal `0.1` even
+    /// when the tokens are passed on without printing them. A base other than
+    /// a single token tree or a path, e.g. `a + b`, is put in parentheses, so
+    /// that the fields are accessed on the whole of it.
+    #[unstable(feature = "proc_macro_field_access", issue = "none")]
+    pub fn field_access(base: TokenStream, path: &[FieldAccess], span: Span) -> TokenStream {
+        let mut trees = base.clone().into_iter();
+        let needs_parens =
+            trees.next().is_some() && trees.next().is_some() && !is_path(base.clone());
+        let base = if needs_parens { base.parenthesize(false, span) } else { base };
+        let mut tokens: Vec<TokenTree> = base.into_iter().collect();
+        for field in path {
+            let mut dot = Punct::new('.', Spacing::Alone);
+            dot.set_span(span);
+            tokens.push(TokenTree::Punct(dot));
+            tokens.push(match field {
+                FieldAccess::Named(name) => TokenTree::Ident(name.clone()),
+                FieldAccess::Indexed(index) => {
+                    TokenTree::Literal(Literal::tuple_index(*index, span))
+                }
+            });
+        }
+        tokens.into_iter().collect()
+    }
+
+    /// Creates the tokens of a `#[doc = "..."]` attribute with `text` as its
+    /// contents, or of `#![doc = "..."]` if `inner` is set. All tokens get
+    /// the given `span`.
+    ///
+    /// The string literal is escaped such that it unescapes to exactly
+    /// `text`, whatever characters it contains. Unlike for `///` comments, no
+    /// whitespace is added in front of `text`.
+    #[unstable(feature = "proc_macro_doc_attr", issue = "none")]
+    pub fn doc_attr(text: &str, inner: bool, span: Span) -> TokenStream {
+        let punct = |ch| {
+            let mut punct = Punct::new(ch, Spacing::Alone);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+        let mut literal = Literal::string(text);
+        literal.set_span(span);
+        let attr = [Ident::new("doc", span).into(), punct('='), literal.into()];
+        let mut group = Group::new(Delimiter::Bracket, attr.into_iter().collect());
+        group.set_span(span);
+        let mut tokens = vec![punct('#')];
+        if inner {
+            tokens.push(punct('!'));
+        }
+        tokens.push(TokenTree::Group(group));
+        tokens.into_iter().collect()
+    }
+
+    /// Creates the tokens of the attribute `#[path]`, or of `#[path(args)]`
+    /// if `args` are given, e.g. `#[inline]` or `#[allow(dead_code)]`. With
+    /// `inner` set, an inner attribute like `#![path]` is created instead.
+    /// The tokens added here get the given `span`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` is not a path, i.e. a sequence of identifiers
+    /// separated by `::`, optionally starting with `::`.
+    #[unstable(feature = "proc_macro_make_attr", issue = "none")]
+    pub fn make_attr(
+        path: TokenStream,
+        args: Option<TokenStream>,
+        inner: bool,
+        span: Span,
+    ) -> TokenStream {
+        assert!(is_path(path.clone()), "`{path}` is not a path");
+        let mut contents = path;
+        if let Some(args) = args {
+            let mut group = Group::new(Delimiter::Parenthesis, args);
+            group.set_span(span);
+            contents.extend([TokenTree::Group(group)]);
+        }
+        let mut group = Group::new(Delimiter::Bracket, contents);
+        group.set_span(span);
+        let punct = |ch| {
+            let mut punct = Punct::new(ch, Spacing::Alone);
+            punct.set_span(span);
+            TokenTree::Punct(punct)
+        };
+        let mut tokens = vec![punct('#')];
+        if inner {
+            tokens.push(punct('!'));
+        }
+        tokens.push(TokenTree::Group(group));
+        tokens.into_iter().collect()
+    }
+
+    /// Computes a minimal list of token deletions and insertions which turn
+    /// this stream into `new`, for reporting how generated code changed.
+    ///
+    /// Both streams are flattened into sequences of tokens first, as
+    /// described for [`TokenDiff`]. The changes are ordered by their position
+    /// in the streams.
+    #[unstable(feature = "proc_macro_diff", issue = "none")]
+    pub fn diff(&self, new: &TokenStream) -> Vec<TokenDiff> {
+        bridge::client::TokenStream::diff(self.0.clone(), new.0.clone())
+            .into_iter()
+            .map(|bridge::TokenEdit { insert, index, token }| {
+                if insert {
+                    TokenDiff::Insert { index, token }
+                } else {
+                    TokenDiff::Delete { index, token }
+                }
+            })
+            .collect()
+    }
+
+    /// Removes the attributes at the top level of this stream, keeping all
+    /// other tokens, e.g. turning `#[inline] /** Docs */ fn f() {}` into
+    /// `fn f() {}`.
+    ///
+    /// An attribute is a `#` punctuation, optionally followed by a `!`, which
+    /// is followed by a group delimited by brackets. This includes doc
+    /// comments, which are represented as `#[doc = "..."]` attributes. Tokens
+    /// nested in groups are not affected, so the attributes of e.g. fields
+    /// inside a struct body are kept.
+    #[unstable(feature = "proc_macro_strip_attributes", issue = "none")]
+    pub fn strip_attributes(self) -> TokenStream {
+        let trees: Vec<TokenTree> = self.into_iter().collect();
+        let is_punct = |tree: &TokenTree, ch| match tree {
+            TokenTree::Punct(punct) => punct.as_char() == ch,
+            _ => false,
+        };
+        let is_brackets = |tree: &TokenTree| match tree {
+            TokenTree::Group(group) => group.delimiter() == Delimiter::Bracket,
+            _ => false,
+        };
+        let mut keep = vec![true; trees.len()];
+        let mut i = 0;
+        while i < trees.len() {
+            let attr_len = match &trees[i..] {
+                [hash, group, ..] if is_punct(hash, '#') && is_brackets(group) => 2,
+                [hash, bang, group, ..]
+                    if is_punct(hash, '#') && is_punct(bang, '!') && is_brackets(group) =>
+                {
+                    3
+                }
+                _ => 1,
+            };
+            if attr_len > 1 {
+                keep[i..i + attr_len].fill(false);
+            }
+            i += attr_len;
+        }
+        trees.into_iter().zip(keep).filter_map(|(tree, keep)| keep.then_some(tree)).collect()
+    }
+
+    /// Creates a stream of `count` copies of this stream, with `separator`
+    /// placed between each two of them if given, e.g. `a, a, a` for the
+    /// stream `a` repeated 3 times with a `,` separator.
+    ///
+    /// This builds the result in a single pass, which is cheaper than
+    /// extending a stream in a loop.
+    #[unstable(feature = "proc_macro_repeat", issue = "none")]
+    pub fn repeat(&self, count: usize, separator: Option<TokenTree>) -> TokenStream {
+        if count == 0 {
+            return TokenStream::new();
+        }
+        let separator = separator.map(tree_to_bridge_tree);
+        TokenStream(Some(bridge::client::TokenStream::repeat(self.0.clone(), count, separator)))
+    }
+
+    /// Returns the kind of the first token tree in this stream, or `None` if
+    /// the stream is empty.
+    ///
+    /// This is the same as matching on the first tree produced by iterating
+    /// the stream, but avoids converting the stream into trees, which makes
+    /// it suitable for dispatching in performance-sensitive parsers.
+    #[unstable(feature = "proc_macro_first_kind", issue = "none")]
+    pub fn first_kind(&self) -> Option<TokenTreeKind> {
+        let kind = bridge::client::TokenStream::first_kind(self.0.as_ref()?)?;
+        Some(match kind {
+            0 => TokenTreeKind::Group,
+            1 => TokenTreeKind::Ident,
+            2 => TokenTreeKind::Punct,
+            3 => TokenTreeKind::Literal,
+            _ => unreachable!("invalid token tree kind {kind}"),
+        })
+    }
+
+    /// Returns the span of the token tree at index `n` at the top level of
+    /// this stream, counting from zero, or `None` if the stream has fewer
+    /// trees. Trees nested in groups are not counted.
+    ///
+    /// Trees are counted as they are produced by iterating the stream, so
+    /// e.g. `+=` counts as two `Punct`s. This allows pointing diagnostics at
+    /// e.g. the third argument of a macro taking a list of identifiers, but
+    /// without transferring all the trees from the compiler like iterating
+    /// would.
+    #[unstable(feature = "proc_macro_nth_token_span", issue = "none")]
+    pub fn nth_token_span(&self, n: usize) -> Option<Span> {
+        self.0.as_ref()?.nth_span(n).map(Span)
+    }
+
+    /// Checks that this stream parses as a sequence of items, like the output
+    /// of a derive or attribute macro, returning the parser's error message
+    /// otherwise. An empty stream is a valid (empty) sequence of items.
+    ///
+    /// This lets a macro detect bugs in the code it generates and report them
+    /// itself, instead of the compiler reporting a parse error in the
+    /// expansion, which is hard for users to make sense of.
+    #[unstable(feature = "proc_macro_validate_as_item", issue = "none")]
+    pub fn validate_as_item(&self) -> Result<(), String> {
+        match &self.0 {
+            Some(stream) => stream.validate_as_item(),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that this stream parses as a single expression, returning the
+    /// parser's error message otherwise. An empty stream is not a valid
+    /// expression.
+    #[unstable(feature = "proc_macro_validate_as_expr", issue = "none")]
+    pub fn validate_as_expr(&self) -> Result<(), String> {
+        match &self.0 {
+            Some(stream) => stream.validate_as_expr(),
+            None => Err("expected expression, found end of input".to_string()),
+        }
+    }
+
+    /// Reverses the order of the top-level token trees of this stream, e.g.
+    /// turning `a b c` into `c b a`, which allows building a stream from back
+    /// to front. The contents of groups are kept in order.
+    ///
+    /// All punctuation characters are made `Spacing::Alone`, as a `Joint`
+    /// character would otherwise be joined with the character which used to
+    /// precede it, so e.g. `+=` becomes `= +` rather than `=+`.
+    #[unstable(feature = "proc_macro_reverse_top_level", issue = "none")]
+    pub fn reverse_top_level(self) -> TokenStream {
+        let mut trees: Vec<TokenTree> = self.into_iter().collect();
+        trees.reverse();
+        trees
+            .into_iter()
+            .map(|tree| match tree {
+                TokenTree::Punct(punct) if punct.spacing() == Spacing::Joint => {
+                    let mut alone = Punct::new(punct.as_char(), Spacing::Alone);
+                    alone.set_span(punct.span());
+                    TokenTree::Punct(alone)
+                }
+                tree => tree,
+            })
+            .collect()
+    }
+
+    /// Parses this stream as a comma-separated list of literals, like the
+    /// arguments of an attribute `#[values(1, "two", 3.0)]`. A trailing comma
+    /// is allowed, and an empty stream gives an empty list.
+    ///
+    /// Returns an error pointing at the first token which is out of place,
+    /// e.g. a path where a literal is expected, or a second literal without a
+    /// comma in between. The error can be emitted as is, or extended with
+    /// notes specific to the macro first. Note that negative numbers like
+    /// `-1` consist of a `-` and a literal, so they are rejected as well.
+    #[unstable(feature = "proc_macro_literal_list", issue = "none")]
+    pub fn parse_literal_list(&self) -> Result<Vec<Literal>, Diagnostic> {
+        let mut literals = Vec::new();
+        let mut trees = self.clone().into_iter();
+        loop {
+            match trees.next() {
+                Some(TokenTree::Literal(literal)) => literals.push(literal),
+                Some(tree) => {
+                    return Err(Diagnostic::spanned(tree.span(), Level::Error, "expected literal"));
+                }
+                None => break,
+            }
+            match trees.next() {
+                Some(TokenTree::Punct(punct)) if punct.as_char() == ',' => {}
+                Some(tree) => {
+                    let message = "expected `,` after literal";
+                    return Err(Diagnostic::spanned(tree.span(), Level::Error, message));
+                }
+                None => break,
+            }
+        }
+        Ok(literals)
+    }
+
+    /// Checks whether this stream contains the identifier `name`, searching
+    /// nested groups as well. Only identifiers whose rawness matches `is_raw`
+    /// are considered, so `r#type` is found by `contains_ident("type", true)`.
+    ///
+    /// This gives the same result as walking the token trees manually, but
+    /// avoids converting the whole stream into trees.
+    #[unstable(feature = "proc_macro_contains_ident", issue = "none")]
+    pub fn contains_ident(&self, name: &str, is_raw: bool) -> bool {
+        match &self.0 {
+            Some(stream) => bridge::client::TokenStream::contains_ident(stream, name, is_raw),
+            None => false,
+        }
+    }
 }
 
 /// Attempts to break the string into tokens and parse those tokens into a token stream.
@@ -187,6 +1180,41 @@ fn to_string(&self) -> String {
     }
 }
 
+impl TokenStream {
+    /// Prints the token stream with a choice of layout, unlike `to_string`
+    /// which always uses the compiler's default token spacing.
+    ///
+    /// With `minify` set, tokens are only separated by whitespace where it is
+    /// needed for the output to lex back into the same tokens. Otherwise the
+    /// output is laid out for reading: brace-delimited blocks and statements
+    /// are put on their own lines and indented.
+    ///
+    /// As with `to_string`, `TokenTree::Group`s with `Delimiter::None`
+    /// delimiters are printed without any delimiters.
+    #[unstable(feature = "proc_macro_formatting", issue = "none")]
+    pub fn to_string_formatted(&self, minify: bool) -> String {
+        self.0.as_ref().map(|t| t.to_string_formatted(minify)).unwrap_or_default()
+    }
+
+    /// Prints the token stream in a canonical form, for use as a key when
+    /// caching results computed from it. Streams with the same tokens give
+    /// the same string, regardless of their spans.
+    ///
+    /// This is the minified form of `to_string_formatted`, so whitespace in
+    /// the original source does not matter, while the spellings of tokens
+    /// do, e.g. `0x10` and `16` are printed differently. Since the string
+    /// lexes back into the same tokens, different token sequences give
+    /// different strings, e.g. the literal and identifier in `"x" as` are
+    /// not confused with the suffixed literal `"x"as`. Invisible delimiters
+    /// are not printed, so they are the only difference which is not kept.
+    /// The exact form may change between compiler versions, so the strings
+    /// should not be persisted across them.
+    #[unstable(feature = "proc_macro_formatting", issue = "none")]
+    pub fn canonical_string(&self) -> String {
+        self.to_string_formatted(true)
+    }
+}
+
 /// Prints the token stream as a string that is supposed to be losslessly convertible back
 /// into the same token stream (modulo spans), except for possibly `TokenTree::Group`s
 /// with `Delimiter::None` delimiters and negative numeric literals.
@@ -216,6 +1244,64 @@ fn default() -> Self {
 #[unstable(feature = "proc_macro_quote", issue = "54722")]
 pub use quote::{quote, quote_span};
 
+/// The prefix of the identifiers standing in for the placeholders of a
+/// `TokenStream::template` until the arguments are spliced in.
+const TEMPLATE_ARG_PREFIX: &str = "__proc_macro_template_arg_";
+
+/// Replaces the placeholder identifiers in `stream`, including those in
+/// nested groups, by the corresponding streams of `args`.
+fn splice_template_args(stream: TokenStream, args: &[TokenStream]) -> TokenStream {
+    stream
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Ident(ident) => {
+                let arg = ident.0.sym.with(|sym| {
+                    let index = sym.strip_prefix(TEMPLATE_ARG_PREFIX)?.parse::<usize>().ok()?;
+                    args.get(index).filter(|_| !ident.0.is_raw)
+                });
+                match arg {
+                    Some(arg) => arg.clone(),
+                    None => TokenStream::from(TokenTree::Ident(ident)),
+                }
+            }
+            TokenTree::Group(group) => {
+                let stream = splice_template_args(group.stream(), args);
+                let mut spliced = Group::new(group.delimiter(), stream);
+                spliced.0.span = group.0.span;
+                TokenStream::from(TokenTree::Group(spliced))
+            }
+            tree => TokenStream::from(tree),
+        })
+        .collect()
+}
+
+/// Returns whether `stream` is a path like `a::b`, i.e. consists of
+/// identifiers separated by `::`, optionally starting with `::`.
+fn is_path(stream: TokenStream) -> bool {
+    let mut trees = stream.into_iter();
+    let mut at_start = true;
+    let mut after_ident = false;
+    while let Some(tree) = trees.next() {
+        match tree {
+            TokenTree::Ident(_) if !after_ident => after_ident = true,
+            TokenTree::Punct(punct)
+                if punct.as_char() == ':'
+                    && punct.spacing() == Spacing::Joint
+                    && (after_ident || at_start) =>
+            {
+                match trees.next() {
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+                    _ => return false,
+                }
+                after_ident = false;
+            }
+            _ => return false,
+        }
+        at_start = false;
+    }
+    after_ident
+}
+
 fn tree_to_bridge_tree(
     tree: TokenTree,
 ) -> bridge::TokenTree<bridge::client::TokenStream, bridge::client::Span, bridge::client::Symbol> {
@@ -469,6 +1555,17 @@ pub fn mixed_site() -> Span {
         Span(bridge::client::Span::mixed_site())
     }
 
+    /// The span covering all the tokens passed to the current procedural
+    /// macro, from the start of the first token to the end of the last one.
+    /// For attribute macros this covers the attribute's arguments.
+    ///
+    /// Falls back to [`Span::call_site`] if the input is empty, and for
+    /// derive macros.
+    #[unstable(feature = "proc_macro_invocation_args_span", issue = "none")]
+    pub fn invocation_args() -> Span {
+        Span(bridge::client::FreeFunctions::invocation_args_span())
+    }
+
     /// The original source file into which this span points.
     #[unstable(feature = "proc_macro_span", issue = "54725")]
     pub fn source_file(&self) -> SourceFile {
@@ -496,7 +1593,21 @@ pub fn byte_range(&self) -> Range<usize> {
         self.0.byte_range()
     }
 
+    /// Returns the length of the span in bytes, the length of `byte_range`.
+    ///
+    /// This is e.g. the length of a literal's text as written in the source,
+    /// escapes and all. Empty spans, like those from `start` and `end`, and
+    /// dummy spans have a length of zero.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn len(&self) -> usize {
+        self.byte_range().len()
+    }
+
     /// Creates an empty span pointing to directly before this span.
+    ///
+    /// The returned span keeps the hygiene context of this span, so when this
+    /// span covers a token, it is the right place for inserting text in front
+    /// of that token, e.g. for suggesting to add `mut` before a binding.
     #[unstable(feature = "proc_macro_span", issue = "54725")]
     pub fn start(&self) -> Span {
         Span(self.0.start())
@@ -508,6 +1619,43 @@ pub fn end(&self) -> Span {
         Span(self.0.end())
     }
 
+    /// Creates a span covering just the first character of this span, for
+    /// diagnostics which point at a single position with one `^`.
+    ///
+    /// Unlike the empty span returned by `start`, which lies between two
+    /// characters, the returned span covers a character, so it is rendered
+    /// under that character and its source text is that character. For an
+    /// empty span, the character following it is covered.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn point(&self) -> Span {
+        Span(self.0.point())
+    }
+
+    /// Extends the end of this span past the whitespace following it, up to
+    /// the start of the next token, e.g. for a suggestion to remove an item
+    /// from a list together with the separator after it.
+    ///
+    /// Only whitespace is skipped, so the span stops in front of a comment.
+    /// The span is returned unchanged if nothing but whitespace follows it up
+    /// to the end of the file, and if its source code is not available.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn extend_to_next_token(&self) -> Span {
+        Span(self.0.extend_to_next_token())
+    }
+
+    /// Creates a span from the start of this span to the end of the line on
+    /// which it starts, excluding the line break, e.g. for a diagnostic about
+    /// everything following a token on its line. The last line of a file
+    /// ends at the end of the file.
+    ///
+    /// For a span which ends on a later line, the returned span is shorter.
+    /// The span is returned unchanged if it does not point into a real source
+    /// file whose source code is available.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn to_end_of_line(&self) -> Span {
+        Span(self.0.to_end_of_line())
+    }
+
     /// The one-indexed line of the source file where the span starts.
     ///
     /// To obtain the line of the span's end, use `span.end().line()`.
@@ -524,6 +1672,42 @@ pub fn column(&self) -> usize {
         self.0.column()
     }
 
+    /// Returns the start and end locations of each of `spans`, as given by
+    /// `line` and `column` on `span.start()` and `span.end()`.
+    ///
+    /// This is considerably faster than querying each span separately when
+    /// locating many spans at once.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn locations(spans: &[Span]) -> Vec<(LineColumn, LineColumn)> {
+        bridge::client::Span::locations(spans.iter().map(|span| span.0).collect())
+            .into_iter()
+            .map(|range| (range.start, range.end))
+            .collect()
+    }
+
+    /// Returns the file path, one-indexed line, and one-indexed column of the
+    /// start of this span, the same values that `core::panic::Location` holds
+    /// for a `#[track_caller]` call at that position.
+    ///
+    /// This allows generating location data at compile time, without querying
+    /// `source_file`, `line` and `column` separately.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn caller_location(&self) -> (String, u32, u32) {
+        let bridge::CallerLocation { file, line, column } = self.0.caller_location();
+        (file, line, column)
+    }
+
+    /// Returns the path of the source file and the start and end positions of
+    /// this span as plain data, e.g. to pass them on to external tools.
+    ///
+    /// Columns are one-indexed and counted in characters, like for
+    /// [`Span::locations`]. The path is empty for spans which do not point
+    /// into a real source file, like those of tokens created from strings.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn to_portable(&self) -> PortableSpan {
+        self.0.to_portable()
+    }
+
     /// Creates a new span encompassing `self` and `other`.
     ///
     /// Returns `None` if `self` and `other` are from different files.
@@ -564,6 +1748,122 @@ pub fn source_text(&self) -> Option<String> {
         self.0.source_text()
     }
 
+    /// Returns `true` if this span was generated by the current macro rather
+    /// than taken from its input.
+    ///
+    /// A span counts as generated when it carries the hygiene of
+    /// [`Span::def_site`] or [`Span::mixed_site`] for the current expansion.
+    /// Spans of input tokens, and spans created with [`Span::call_site`],
+    /// are not considered generated.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn is_generated(&self) -> bool {
+        self.0.is_generated()
+    }
+
+    /// Returns the name of the macro whose expansion produced this span,
+    /// formatted the way the compiler refers to it in diagnostics (`name!`,
+    /// `#[name]` or `#[derive(Name)]`).
+    ///
+    /// Returns `None` if the span was not produced by a macro expansion, for
+    /// example if it points directly into a source file.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn expansion_name(&self) -> Option<String> {
+        self.0.expansion_name()
+    }
+
+    /// Returns a span starting at the same position as this one, but at most
+    /// `max_bytes` long, for pointing at just the start of a large construct.
+    ///
+    /// The returned span always lies within `self`. Spans that are already
+    /// short enough are returned unchanged.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn truncate(&self, max_bytes: usize) -> Span {
+        Span(self.0.truncate(max_bytes))
+    }
+
+    /// Returns `true` if `other` lies entirely within `self`, e.g. to check
+    /// that a user-written fragment is part of an expected region.
+    ///
+    /// Spans in different source files never contain each other.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn contains(&self, other: Span) -> bool {
+        self.0.contains(other.0)
+    }
+
+    /// Compares the source positions of two spans, ordering them by their
+    /// source file first, then by their start, and then by their end.
+    ///
+    /// This allows sorting spans, e.g. to emit diagnostics in source order.
+    /// Spans in different files are ordered by the order in which the files
+    /// were loaded, which is stable within a compilation but may change
+    /// between builds.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn source_cmp(&self, other: &Span) -> Ordering {
+        self.0.source_cmp(other.0)
+    }
+
+    /// Returns whether the two spans were produced by the same macro
+    /// expansion, or both are from code which is not produced by any.
+    ///
+    /// Only the innermost expansion is compared. The spans of tokens written
+    /// in the source are from no expansion, while those created by the
+    /// running macro with `Span::call_site()` are from its own expansion.
+    /// Unlike `eq`, this ignores the source locations of the spans, so tokens
+    /// at different places can be grouped by the expansion producing them.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn same_expansion(&self, other: &Span) -> bool {
+        self.0.same_expansion(other.0)
+    }
+
+    /// Returns whether this is a dummy span, which does not point to any
+    /// source code, so diagnostics using it have no location.
+    ///
+    /// The compiler uses such spans as placeholders where no location is
+    /// known. A macro finding one in its output can replace it with e.g.
+    /// `Span::call_site()` to make diagnostics point somewhere useful.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn is_dummy(&self) -> bool {
+        self.0.is_dummy()
+    }
+
+    /// Returns whether this span comes from a file pulled in with `include!`,
+    /// so the tokens are attributed to the included file rather than to the
+    /// module the macro is expanded in.
+    ///
+    /// This is a heuristic. A span counts as included if an `include!` call
+    /// appears in its chain of macro expansions, or if, after following that
+    /// chain back to the original call site, it lies in a different file than
+    /// the one defining the current module. Spans from other crates and dummy
+    /// spans are never considered included.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn is_included(&self) -> bool {
+        self.0.is_included()
+    }
+
+    /// Returns the full text of the source line on which this span starts,
+    /// without the line terminator, e.g. for quoting the offending line in a
+    /// diagnostic rather than just the text of the span.
+    ///
+    /// Unlike `source_text`, which also returns the text of code that macros
+    /// parsed from strings, this only returns a result for spans pointing into
+    /// real source files.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn line_text(&self) -> Option<String> {
+        self.0.line_text()
+    }
+
+    /// Returns the indentation of the source line on which this span starts,
+    /// i.e. the spaces and tabs at the start of `line_text`.
+    ///
+    /// This allows generated code to be indented consistently with the
+    /// surrounding source. Returns `None` in the same cases as `line_text`.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn line_indent(&self) -> Option<String> {
+        let line = self.line_text()?;
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        Some(line[..indent_len].to_owned())
+    }
+
     // Used by the implementation of `Span::quote`
     #[doc(hidden)]
     #[unstable(feature = "proc_macro_internals", issue = "27812")]
@@ -592,6 +1892,35 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// A line and column in a source file, as returned by [`Span::locations`].
+#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+    /// The one-indexed line in the source file.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub line: usize,
+    /// The one-indexed column in the source file, counted in characters.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub column: usize,
+}
+
+/// The location of a span in the form of plain data, as returned by
+/// [`Span::to_portable`].
+#[unstable(feature = "proc_macro_span", issue = "54725")]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PortableSpan {
+    /// The path of the source file, or an empty string if the span does not
+    /// point into a real source file.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub file_path: String,
+    /// The position of the start of the span.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub start: LineColumn,
+    /// The position of the end of the span.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub end: LineColumn,
+}
+
 /// The source file of a given `Span`.
 #[unstable(feature = "proc_macro_span", issue = "54725")]
 #[derive(Clone)]
@@ -613,6 +1942,71 @@ pub fn path(&self) -> PathBuf {
         PathBuf::from(self.0.path())
     }
 
+    /// Compares the paths of two source files as strings, for processing
+    /// files in a deterministic order regardless of the order in which they
+    /// were loaded.
+    ///
+    /// Files which are not real, like ones created by macros, are compared by
+    /// the name they are displayed with in diagnostics, e.g. `<proc-macro
+    /// source code>`. Unlike `path`, this never panics.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn path_cmp(&self, other: &SourceFile) -> Ordering {
+        self.0.path_cmp(&other.0)
+    }
+
+    /// Returns the source file containing the byte position `pos`, or `None`
+    /// if no file contains it.
+    ///
+    /// Note that `pos` is an offset into the compiler's global source map,
+    /// which covers all loaded files, and not a position within a file such
+    /// as the ones returned by [`Span::byte_range`].
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn at_position(pos: u32) -> Option<SourceFile> {
+        bridge::client::SourceFile::source_file_at(pos).map(SourceFile)
+    }
+
+    /// Returns the source file in which the running macro is defined, the
+    /// one that `Span::def_site()` points into.
+    ///
+    /// This lets a macro refer to its own definition in diagnostics, e.g. in
+    /// a "defined here" note. The file belongs to the crate defining the
+    /// macro, so its path is the one it had when that crate was compiled.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn def_site() -> SourceFile {
+        Span::def_site().source_file()
+    }
+
+    /// Adds a source file called `name` with the given `contents` to the
+    /// compiler's source map, so that spans pointing into it can be created
+    /// with [`SourceFile::span`].
+    ///
+    /// This allows macros which generate code from other files, e.g. from
+    /// `.proto` definitions, to report errors at the right place in those
+    /// files. The new file is not real (see [`SourceFile::is_real`]), but
+    /// `Span::source_text` works for spans into it. If a file with the same
+    /// name was registered before, that file is returned and `contents` is
+    /// ignored.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn register_virtual(name: &str, contents: String) -> SourceFile {
+        SourceFile(bridge::client::SourceFile::register_virtual(name, contents))
+    }
+
+    /// Returns a span covering the given byte range of this file, with the
+    /// hygiene of `Span::call_site()`.
+    ///
+    /// The file does not need to be the one containing the macro call, so this
+    /// allows diagnostics with notes pointing into other files, e.g. to the
+    /// definition of something the macro input refers to. Together with
+    /// `register_virtual`, notes can also point into files which are not part
+    /// of the crate.
+    ///
+    /// Returns `None` if the range is out of bounds, or if it does not start
+    /// and end on character boundaries.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn span(&self, range: Range<usize>) -> Option<Span> {
+        self.0.span(range).map(Span)
+    }
+
     /// Returns `true` if this source file is a real source file, and not generated by an external
     /// macro's expansion.
     #[unstable(feature = "proc_macro_span", issue = "54725")]
@@ -798,6 +2192,19 @@ pub enum Delimiter {
     None,
 }
 
+/// Where the invisible delimiters of a `Group` with `Delimiter::None` come
+/// from, as returned by `Group::none_delim_origin`.
+#[unstable(feature = "proc_macro_none_delim_origin", issue = "none")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum NoneDelimOrigin {
+    /// The group was created by a procedural macro, or passed through one.
+    Authored,
+    /// The group was created by the compiler around a fragment captured by a
+    /// `macro_rules!` metavariable like `$e:expr`, and substituted into the
+    /// tokens passed to the macro.
+    Interpolated,
+}
+
 impl Group {
     /// Creates a new `Group` with the given delimiter and token stream.
     ///
@@ -810,15 +2217,57 @@ pub fn new(delimiter: Delimiter, stream: TokenStream) -> Group {
             delimiter,
             stream: stream.0,
             span: bridge::DelimSpan::from_single(Span::call_site().0),
+            interpolated: false,
         })
     }
 
+    /// Creates a new `Group` from the opening character of its delimiter,
+    /// `(`, `{` or `[`, and the given token stream.
+    ///
+    /// Returns an error for any other character, including closing delimiters.
+    /// Like `Group::new`, this sets the span of the group to
+    /// `Span::call_site()`.
+    #[unstable(feature = "proc_macro_group_from_char", issue = "none")]
+    pub fn from_delim_char(ch: char, stream: TokenStream) -> Result<Group, ()> {
+        let delimiter = match ch {
+            '(' => Delimiter::Parenthesis,
+            '{' => Delimiter::Brace,
+            '[' => Delimiter::Bracket,
+            _ => return Err(()),
+        };
+        Ok(Group::new(delimiter, stream))
+    }
+
     /// Returns the delimiter of this `Group`
     #[stable(feature = "proc_macro_lib2", since = "1.29.0")]
     pub fn delimiter(&self) -> Delimiter {
         self.0.delimiter
     }
 
+    /// For a group with `Delimiter::None`, returns whether the compiler
+    /// created it around an interpolated `macro_rules!` fragment, or whether
+    /// it was created by a macro. Returns `None` for other delimiters.
+    ///
+    /// Interpolated groups hold a single parsed fragment, e.g. an expression,
+    /// which has to be kept together to preserve its precedence. Invisible
+    /// groups written by macros carry no such guarantee. Note that some
+    /// fragments passed to attribute and derive macros are flattened by the
+    /// compiler for backwards compatibility (see #73345), and are passed as
+    /// their plain tokens without any group.
+    ///
+    /// The origin is not part of the tokens returned to the compiler: groups
+    /// created with `Group::new`, and interpolated groups passed on to another
+    /// macro, are reported as `NoneDelimOrigin::Authored`. Invisible groups
+    /// are parsed the same way regardless of their origin.
+    #[unstable(feature = "proc_macro_none_delim_origin", issue = "none")]
+    pub fn none_delim_origin(&self) -> Option<NoneDelimOrigin> {
+        match (self.0.delimiter, self.0.interpolated) {
+            (Delimiter::None, false) => Some(NoneDelimOrigin::Authored),
+            (Delimiter::None, true) => Some(NoneDelimOrigin::Interpolated),
+            _ => None,
+        }
+    }
+
     /// Returns the `TokenStream` of tokens that are delimited in this `Group`.
     ///
     /// Note that the returned token stream does not include the delimiter
@@ -872,6 +2321,36 @@ pub fn span_close(&self) -> Span {
     pub fn set_span(&mut self, span: Span) {
         self.0.span = bridge::DelimSpan::from_single(span.0);
     }
+
+    /// Creates a `Group` with the delimiters and delimiter spans of this
+    /// group, but containing `stream`, whose tokens get this group's span.
+    ///
+    /// Tokens in nested groups are respanned as well, so that diagnostics for
+    /// generated code placed in e.g. a user's block point at that block.
+    #[unstable(feature = "proc_macro_group_respanned", issue = "none")]
+    pub fn with_stream_respanned(&self, stream: TokenStream) -> Group {
+        let stream = respan_stream(stream, self.span());
+        Group(bridge::Group {
+            delimiter: self.0.delimiter,
+            stream: stream.0,
+            span: self.0.span,
+            interpolated: false,
+        })
+    }
+}
+
+/// Sets the span of every token in `stream` to `span`, recursing into groups.
+fn respan_stream(stream: TokenStream, span: Span) -> TokenStream {
+    stream
+        .into_iter()
+        .map(|mut tree| {
+            if let TokenTree::Group(group) = &mut tree {
+                *group = Group::new(group.delimiter(), respan_stream(group.stream(), span));
+            }
+            tree.set_span(span);
+            tree
+        })
+        .collect()
 }
 
 // N.B., the bridge only provides `to_string`, implement `fmt::Display`
@@ -1085,6 +2564,119 @@ pub fn new_raw(string: &str, span: Span) -> Ident {
         })
     }
 
+    /// Creates identifiers with the given names, which are raw (`r#ident`)
+    /// where the accompanying flag is set, all with the given span.
+    ///
+    /// Each name is validated like by `Ident::new` and `Ident::new_raw`, but
+    /// instead of panicking, an error naming the index of the first invalid
+    /// name is returned. ASCII names are validated without any requests to
+    /// the compiler, which makes this cheap even for many names.
+    #[unstable(feature = "proc_macro_new_many", issue = "none")]
+    pub fn new_many(names: &[(&str, bool)], span: Span) -> Result<Vec<Ident>, String> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(i, &(name, is_raw))| {
+                let sym = bridge::client::Symbol::try_new_ident(name, is_raw)
+                    .map_err(|msg| format!("identifier {i}: {msg}"))?;
+                Ok(Ident(bridge::Ident { sym, is_raw, span: span.0 }))
+            })
+            .collect()
+    }
+
+    /// Creates an identifier which is raw (`r#ident`) if `string` is a
+    /// keyword in the edition of `span`, and a plain identifier otherwise.
+    ///
+    /// This keeps generated names like `async` or `try` valid regardless of
+    /// the edition of the code they end up in. Returns an error if `string`
+    /// is not a valid identifier, or is a keyword which cannot be raw, such
+    /// as `self` or `_`.
+    #[unstable(feature = "proc_macro_ident_edition_safe", issue = "none")]
+    pub fn new_edition_safe(string: &str, span: Span) -> Result<Ident, String> {
+        let is_raw = bridge::client::Symbol::is_reserved(string, span.0);
+        let sym = bridge::client::Symbol::try_new_ident(string, is_raw)?;
+        Ok(Ident(bridge::Ident { sym, is_raw, span: span.0 }))
+    }
+
+    /// Creates a non-raw identifier from `name`, appending a `_` if `name` is
+    /// a keyword in the edition of `span`, e.g. `type_` for `type`.
+    ///
+    /// Unlike `Ident::new_edition_safe`, which escapes keywords as raw
+    /// identifiers, this is meant for deriving new names from user-provided
+    /// ones, e.g. naming a method after a field. Like `Ident::new`, this panics
+    /// if `name` is not a valid identifier.
+    #[unstable(feature = "proc_macro_sanitized_ident", issue = "none")]
+    pub fn sanitized(name: &str, span: Span) -> Ident {
+        if bridge::client::Symbol::is_reserved(name, span.0) {
+            Ident::new(&format!("{name}_"), span)
+        } else {
+            Ident::new(name, span)
+        }
+    }
+
+    /// Creates the identifier `{base}{index}`, e.g. `field_0` for
+    /// `Ident::indexed("field_", 0, span)`, as commonly generated for the
+    /// fields of tuple structs.
+    ///
+    /// Like `Ident::new`, this panics if the result is not a valid identifier,
+    /// e.g. if `base` is empty.
+    #[unstable(feature = "proc_macro_indexed_ident", issue = "none")]
+    pub fn indexed(base: &str, index: usize, span: Span) -> Ident {
+        Ident::new(&format!("{base}{index}"), span)
+    }
+
+    /// Returns whether this is the `_` token, which is lexed like an
+    /// identifier but stands for a wildcard pattern or an inferred type
+    /// rather than a name that can be bound or referred to.
+    #[unstable(feature = "proc_macro_is_wildcard", issue = "none")]
+    pub fn is_wildcard(&self) -> bool {
+        !self.0.is_raw && self.0.sym.with(|sym| sym == "_")
+    }
+
+    /// Returns whether this identifier is not raw, but would have to be raw
+    /// (`r#ident`) to be valid as a name, because it is a keyword in the
+    /// edition of its span, e.g. `async` in the 2018 edition.
+    ///
+    /// Keywords which cannot be raw identifiers, like `self` and `crate`, are
+    /// not considered to need it. Identifiers created by
+    /// `Ident::new_edition_safe` never need to be made raw.
+    #[unstable(feature = "proc_macro_needs_raw", issue = "none")]
+    pub fn needs_raw(&self) -> bool {
+        if self.0.is_raw {
+            return false;
+        }
+        let name = self.0.sym.with(|sym| sym.to_owned());
+        bridge::client::Symbol::can_be_raw(&name)
+            && bridge::client::Symbol::is_reserved(&name, self.0.span)
+    }
+
+    /// Returns an integer identifying the name of this identifier, so that
+    /// two identifiers with the same (normalized) name have the same id. This
+    /// allows keying maps by integers instead of hashing names repeatedly.
+    /// Whether the identifier is raw is not taken into account.
+    ///
+    /// The id is only meaningful while the current macro invocation runs:
+    /// ids are not reused across invocations, so they must not be stored
+    /// between them, serialized, or compared with ids from other invocations.
+    #[unstable(feature = "proc_macro_symbol_id", issue = "none")]
+    pub fn symbol_id(&self) -> u32 {
+        self.0.sym.as_u32()
+    }
+
+    /// Returns whether a binding with this identifier, e.g. in `let ident`,
+    /// could be referred to by code written at the macro's call site, and so
+    /// may shadow or be shadowed by bindings of the user with the same name.
+    ///
+    /// This is the case when the span of the identifier has the hygiene of the
+    /// code at the call site, like input tokens written there or spans created
+    /// with `Span::call_site`, and not for `Span::mixed_site` or
+    /// `Span::def_site`. The answer is advisory, as it does not take into
+    /// account which names are actually in scope.
+    #[unstable(feature = "proc_macro_user_binding", issue = "none")]
+    pub fn looks_like_user_binding(&self) -> bool {
+        self.0.span.binds_like_call_site()
+    }
+
     /// Returns the span of this `Ident`, encompassing the entire string returned
     /// by [`to_string`](ToString::to_string).
     #[stable(feature = "proc_macro_lib2", since = "1.29.0")]
@@ -1097,6 +2689,17 @@ pub fn span(&self) -> Span {
     pub fn set_span(&mut self, span: Span) {
         self.0.span = span.0;
     }
+
+    /// Returns the identifier exactly as it was written in the source, such
+    /// as `r#Foo`, for echoing it back in diagnostics. This can differ from
+    /// `to_string`, as identifiers are NFC-normalized.
+    ///
+    /// Returns `None` in the same cases as [`Span::source_text`], e.g. if the
+    /// identifier was created by a macro.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn source_text(&self) -> Option<String> {
+        self.span().source_text()
+    }
 }
 
 #[doc(hidden)]
@@ -1137,6 +2740,141 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 #[stable(feature = "proc_macro_lib2", since = "1.29.0")]
 pub struct Literal(bridge::Literal<bridge::client::Span, bridge::client::Symbol>);
 
+/// A numeric type named by the suffix of a literal, as returned by
+/// [`Literal::numeric_suffix`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[unstable(feature = "proc_macro_numeric_suffix", issue = "none")]
+pub enum NumericType {
+    /// `i8`
+    I8,
+    /// `i16`
+    I16,
+    /// `i32`
+    I32,
+    /// `i64`
+    I64,
+    /// `i128`
+    I128,
+    /// `isize`
+    Isize,
+    /// `u8`
+    U8,
+    /// `u16`
+    U16,
+    /// `u32`
+    U32,
+    /// `u64`
+    U64,
+    /// `u128`
+    U128,
+    /// `usize`
+    Usize,
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+}
+
+impl NumericType {
+    fn from_suffix(suffix: &str) -> Option<NumericType> {
+        Some(match suffix {
+            "i8" => NumericType::I8,
+            "i16" => NumericType::I16,
+            "i32" => NumericType::I32,
+            "i64" => NumericType::I64,
+            "i128" => NumericType::I128,
+            "isize" => NumericType::Isize,
+            "u8" => NumericType::U8,
+            "u16" => NumericType::U16,
+            "u32" => NumericType::U32,
+            "u64" => NumericType::U64,
+            "u128" => NumericType::U128,
+            "usize" => NumericType::Usize,
+            "f32" => NumericType::F32,
+            "f64" => NumericType::F64,
+            _ => return None,
+        })
+    }
+}
+
+/// A floating-point type, as taken by [`Literal::float_suffixed`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[unstable(feature = "proc_macro_float_suffixed", issue = "none")]
+pub enum FloatType {
+    /// `f32`
+    F32,
+    /// `f64`
+    F64,
+}
+
+/// The kind of a literal, as taken by [`Literal::reinterpret`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[unstable(feature = "proc_macro_literal_reinterpret", issue = "none")]
+pub enum LiteralKind {
+    /// A byte character, `b'a'`.
+    Byte,
+    /// A character, `'a'`.
+    Char,
+    /// An integer, `1`.
+    Integer,
+    /// A floating-point number, `1.0`.
+    Float,
+    /// A string, `"a"`.
+    Str,
+    /// A raw string with the given number of `#`s, `r#"a"#`.
+    StrRaw(u8),
+    /// A byte string, `b"a"`.
+    ByteStr,
+    /// A raw byte string with the given number of `#`s, `br#"a"#`.
+    ByteStrRaw(u8),
+    /// A C string, `c"a"`.
+    CStr,
+    /// A raw C string with the given number of `#`s, `cr#"a"#`.
+    CStrRaw(u8),
+}
+
+impl LiteralKind {
+    fn to_bridge(self) -> bridge::LitKind {
+        match self {
+            LiteralKind::Byte => bridge::LitKind::Byte,
+            LiteralKind::Char => bridge::LitKind::Char,
+            LiteralKind::Integer => bridge::LitKind::Integer,
+            LiteralKind::Float => bridge::LitKind::Float,
+            LiteralKind::Str => bridge::LitKind::Str,
+            LiteralKind::StrRaw(n) => bridge::LitKind::StrRaw(n),
+            LiteralKind::ByteStr => bridge::LitKind::ByteStr,
+            LiteralKind::ByteStrRaw(n) => bridge::LitKind::ByteStrRaw(n),
+            LiteralKind::CStr => bridge::LitKind::CStr,
+            LiteralKind::CStrRaw(n) => bridge::LitKind::CStrRaw(n),
+        }
+    }
+}
+
+/// The value of a literal together with its type, as returned by
+/// [`Literal::as_const`].
+///
+/// There is no variant for `true` and `false`, which are `Ident`s rather than
+/// literals.
+#[derive(Clone, Debug, PartialEq)]
+#[unstable(feature = "proc_macro_literal_as_const", issue = "none")]
+pub enum ConstValue {
+    /// An integer, e.g. `1u8`, with the type of its suffix if it has one.
+    Int(i128, Option<NumericType>),
+    /// A floating-point number, e.g. `1.5f32`, with the type of its suffix if
+    /// it has one.
+    Float(f64, Option<FloatType>),
+    /// A string, e.g. `"a"`.
+    Str(String),
+    /// A character, e.g. `'a'`.
+    Char(char),
+    /// A byte character, e.g. `b'a'`.
+    Byte(u8),
+    /// A byte string, e.g. `b"a"`.
+    Bytes(Vec<u8>),
+    /// A C string, e.g. `c"a"`, without the terminating nul byte.
+    CStr(Vec<u8>),
+}
+
 macro_rules! suffixed_int_literals {
     ($($name:ident => $kind:ident,)*) => ($(
         /// Creates a new suffixed integer literal with the specified value.
@@ -1229,6 +2967,46 @@ fn new(kind: bridge::LitKind, value: &str, suffix: Option<&str>) -> Self {
         isize_unsuffixed => isize,
     }
 
+    /// Creates a new unsuffixed integer literal with the specified value,
+    /// with its decimal digits grouped by underscores every `group` digits,
+    /// counting from the right, e.g. `1_000_000` for `(1000000, 3)`.
+    ///
+    /// The literal has the same value as the one created by
+    /// `Literal::u128_unsuffixed`, only its representation differs.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `group` is zero.
+    #[unstable(feature = "proc_macro_integer_grouped", issue = "none")]
+    pub fn integer_grouped(n: u128, group: usize) -> Literal {
+        assert!(group != 0, "digit groups must not be empty");
+        let digits = n.to_string();
+        let mut repr = String::with_capacity(digits.len() + digits.len() / group);
+        for (i, digit) in digits.chars().enumerate() {
+            if i != 0 && (digits.len() - i) % group == 0 {
+                repr.push('_');
+            }
+            repr.push(digit);
+        }
+        Literal::new(bridge::LitKind::Integer, &repr, None)
+    }
+
+    /// Creates a new `usize` suffixed integer literal, e.g. `4usize`, if `n`
+    /// fits into a `usize` of the target the crate is compiled for.
+    ///
+    /// `Literal::usize_suffixed` takes a `usize` of the host running the
+    /// macro, which may be larger than the one of the target, e.g. when
+    /// cross-compiling for a 32-bit target. This returns an error for values
+    /// that would overflow on the target instead.
+    #[unstable(feature = "proc_macro_usize_checked", issue = "none")]
+    pub fn usize_checked(n: u128) -> Result<Literal, ()> {
+        let bits = bridge::client::FreeFunctions::target_pointer_width();
+        if bits < u128::BITS && n >> bits != 0 {
+            return Err(());
+        }
+        Ok(Literal::new(bridge::LitKind::Integer, &n.to_string(), Some("usize")))
+    }
+
     /// Creates a new unsuffixed floating-point literal.
     ///
     /// This constructor is similar to those like `Literal::i8_unsuffixed` where
@@ -1319,13 +3097,43 @@ pub fn f64_suffixed(n: f64) -> Literal {
         Literal::new(bridge::LitKind::Float, &n.to_string(), Some("f64"))
     }
 
+    /// Creates a new floating-point literal of the type `ty`, with the value
+    /// `n` and the suffix of that type, e.g. `1.5f32`.
+    ///
+    /// For `FloatType::F32` the value is first converted to an `f32`, and
+    /// formatted with the shortest representation that round-trips as an
+    /// `f32`. This avoids digits like the ones in `0.10000000149011612f32`
+    /// that come from formatting the converted value as an `f64`.
+    ///
+    /// # Panics
+    ///
+    /// This function requires that the value, after conversion to `ty`, is
+    /// finite, for example if it is infinity or NaN this function will panic.
+    #[unstable(feature = "proc_macro_float_suffixed", issue = "none")]
+    pub fn float_suffixed(n: f64, ty: FloatType) -> Literal {
+        match ty {
+            FloatType::F32 => Literal::f32_suffixed(n as f32),
+            FloatType::F64 => Literal::f64_suffixed(n),
+        }
+    }
+
     /// String literal.
     #[stable(feature = "proc_macro_lib2", since = "1.29.0")]
     pub fn string(string: &str) -> Literal {
+        Literal::new(bridge::LitKind::Str, &Literal::escape_for_string(string), None)
+    }
+
+    /// Escapes `string` the way `Literal::string` does, returning the contents
+    /// of the string literal without the surrounding quotes.
+    ///
+    /// This allows building the contents of a string literal piece by piece,
+    /// e.g. joining escaped parts and parsing the quoted result as a
+    /// `Literal`, possibly with a suffix.
+    #[unstable(feature = "proc_macro_escape_for_string", issue = "none")]
+    pub fn escape_for_string(string: &str) -> String {
         let quoted = format!("{:?}", string);
         assert!(quoted.starts_with('"') && quoted.ends_with('"'));
-        let symbol = &quoted[1..quoted.len() - 1];
-        Literal::new(bridge::LitKind::Str, symbol, None)
+        quoted[1..quoted.len() - 1].to_owned()
     }
 
     /// Character literal.
@@ -1363,6 +3171,140 @@ pub fn set_span(&mut self, span: Span) {
         self.0.span = span.0;
     }
 
+    /// Returns this literal with its span replaced by `span`, for use in
+    /// builder-style code. Equivalent to calling `set_span` on it.
+    #[unstable(feature = "proc_macro_literal_with_span", issue = "none")]
+    pub fn with_span(mut self, span: Span) -> Literal {
+        self.set_span(span);
+        self
+    }
+
+    /// Creates the unsuffixed integer literal used to access the field
+    /// `index` of a tuple or tuple struct, e.g. the `0` in `self.0`.
+    ///
+    /// This is the same as `Literal::usize_unsuffixed`, but with the given
+    /// span, which determines where errors about the field access are reported.
+    #[unstable(feature = "proc_macro_indexed_ident", issue = "none")]
+    pub fn tuple_index(index: usize, span: Span) -> Literal {
+        Literal::usize_unsuffixed(index).with_span(span)
+    }
+
+    /// Returns the numeric type named by the suffix of an integer or float
+    /// literal, e.g. `NumericType::U8` for `1u8`.
+    ///
+    /// Returns `None` if the literal is not numeric, has no suffix, or has a
+    /// suffix which is not valid for it, like `1.0u8` or `1ms`.
+    #[unstable(feature = "proc_macro_numeric_suffix", issue = "none")]
+    pub fn numeric_suffix(&self) -> Option<NumericType> {
+        let ty = self.0.suffix?.with(NumericType::from_suffix)?;
+        match (self.0.kind, ty) {
+            (bridge::LitKind::Integer, _) => Some(ty),
+            (bridge::LitKind::Float, NumericType::F32 | NumericType::F64) => Some(ty),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a float literal, e.g. `1500.0` for `1.5e3` or
+    /// `-0.25` for a literal created by `Literal::f64_unsuffixed(-0.25)`.
+    ///
+    /// Integer literals with an `f32` or `f64` suffix, like `1f32`, are floats
+    /// as well. The value is parsed as an `f64` regardless of the suffix.
+    /// Digit separators are ignored, so `1_000.000_1` gives `1000.0001`.
+    /// Returns `None` for other literals, and for values that overflow to
+    /// infinity.
+    #[unstable(feature = "proc_macro_float_value", issue = "none")]
+    pub fn float_value(&self) -> Option<f64> {
+        match self.0.kind {
+            bridge::LitKind::Float => {}
+            bridge::LitKind::Integer
+                if matches!(self.numeric_suffix(), Some(NumericType::F32 | NumericType::F64)) => {}
+            _ => return None,
+        }
+        let value: f64 = self.0.symbol.with(|symbol| symbol.replace('_', "").parse().ok())?;
+        value.is_finite().then_some(value)
+    }
+
+    /// Compares the numeric values of two integer or float literals, e.g.
+    /// `0x10` equals `16u8`, and `1` equals `1.0`. Suffixes are not taken
+    /// into account. Returns `None` if either literal is not numeric, or if
+    /// its value cannot be represented.
+    ///
+    /// Integers are compared exactly with each other. When comparing an
+    /// integer with a float, the integer is converted to an `f64` first,
+    /// which loses precision for integers of more than 53 bits, so e.g.
+    /// `9007199254740993` compares equal to `9007199254740992.0`.
+    #[unstable(feature = "proc_macro_numeric_eq", issue = "none")]
+    pub fn numeric_eq(&self, other: &Literal) -> Option<bool> {
+        let to_f64 = |(negative, n): (bool, u128)| if negative { -(n as f64) } else { n as f64 };
+        match (self.integer_value(), other.integer_value()) {
+            // Zero is equal to itself regardless of its sign.
+            (Some(a), Some(b)) => Some(a == b || (a.1 == 0 && b.1 == 0)),
+            (Some(a), None) => Some(to_f64(a) == other.float_value()?),
+            (None, Some(b)) => Some(self.float_value()? == to_f64(b)),
+            (None, None) => Some(self.float_value()? == other.float_value()?),
+        }
+    }
+
+    /// Decodes the value of this literal together with its type, e.g.
+    /// `ConstValue::Int(16, Some(NumericType::U8))` for `0x10u8`. Escapes in
+    /// string and character literals are processed, and raw literals are
+    /// supported. Digit separators in numbers are ignored, so `0xDE_AD_BE_EF`
+    /// gives `ConstValue::Int(0xDEADBEEF, None)`.
+    ///
+    /// Returns `None` for integers which do not fit into an `i128`, floats
+    /// which overflow to infinity, and literals which are invalid in other
+    /// ways, like strings with invalid escapes.
+    #[unstable(feature = "proc_macro_literal_as_const", issue = "none")]
+    pub fn as_const(&self) -> Option<ConstValue> {
+        let suffix = self.numeric_suffix();
+        if let Some(value) = self.float_value() {
+            let ty = match suffix {
+                Some(NumericType::F32) => Some(FloatType::F32),
+                Some(NumericType::F64) => Some(FloatType::F64),
+                _ => None,
+            };
+            return Some(ConstValue::Float(value, ty));
+        }
+        if let Some((negative, n)) = self.integer_value() {
+            let value =
+                if negative { 0i128.checked_sub_unsigned(n)? } else { i128::try_from(n).ok()? };
+            return Some(ConstValue::Int(value, suffix));
+        }
+        let bytes = bridge::client::FreeFunctions::unescape_literal(self.0.clone())?;
+        Some(match self.0.kind {
+            bridge::LitKind::Str | bridge::LitKind::StrRaw(_) => {
+                ConstValue::Str(String::from_utf8(bytes).ok()?)
+            }
+            bridge::LitKind::Char => ConstValue::Char(String::from_utf8(bytes).ok()?.pop()?),
+            bridge::LitKind::Byte => ConstValue::Byte(*bytes.first()?),
+            bridge::LitKind::ByteStr | bridge::LitKind::ByteStrRaw(_) => ConstValue::Bytes(bytes),
+            bridge::LitKind::CStr | bridge::LitKind::CStrRaw(_) => ConstValue::CStr(bytes),
+            _ => return None,
+        })
+    }
+
+    /// Returns the sign and magnitude of an integer literal, which can be
+    /// negative when created through e.g. `Literal::i32_unsuffixed`.
+    fn integer_value(&self) -> Option<(bool, u128)> {
+        if self.0.kind != bridge::LitKind::Integer || self.float_value().is_some() {
+            return None;
+        }
+        self.0.symbol.with(|symbol| {
+            let (negative, symbol) = match symbol.strip_prefix('-') {
+                Some(symbol) => (true, symbol),
+                None => (false, symbol),
+            };
+            let (radix, digits) = match symbol.as_bytes() {
+                [b'0', b'x', ..] => (16, &symbol[2..]),
+                [b'0', b'o', ..] => (8, &symbol[2..]),
+                [b'0', b'b', ..] => (2, &symbol[2..]),
+                _ => (10, symbol),
+            };
+            let n = u128::from_str_radix(&digits.replace('_', ""), radix).ok()?;
+            Some((negative, n))
+        })
+    }
+
     /// Returns a `Span` that is a subset of `self.span()` containing only the
     /// source bytes in range `range`. Returns `None` if the would-be trimmed
     /// span is outside the bounds of `self`.
@@ -1379,6 +3321,101 @@ pub fn subspan<R: RangeBounds<usize>>(&self, range: R) -> Option<Span> {
         self.0.span.subspan(range.start_bound().cloned(), range.end_bound().cloned()).map(Span)
     }
 
+    /// Returns the span of the contents of a string literal, leaving out the
+    /// quotes as well as the prefix and `#`s of byte, C and raw strings, e.g.
+    /// the span of `abc` in `r#"abc"#`. Escapes in the contents are covered
+    /// as written, so the result can be used to suggest replacing them.
+    ///
+    /// Returns `None` for literals other than strings, and, like `subspan`,
+    /// if the span of this literal is too short to contain the string.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn string_content_span(&self) -> Option<Span> {
+        let start = match self.0.kind {
+            bridge::LitKind::Str => 1,
+            bridge::LitKind::ByteStr | bridge::LitKind::CStr => 2,
+            bridge::LitKind::StrRaw(n) => 2 + n as usize,
+            bridge::LitKind::ByteStrRaw(n) | bridge::LitKind::CStrRaw(n) => 3 + n as usize,
+            _ => return None,
+        };
+        let len = self.0.symbol.with(|symbol| symbol.len());
+        self.subspan(start..start + len)
+    }
+
+    /// Makes the span of this literal point at source text which matches
+    /// the literal, keeping the span's hygiene.
+    ///
+    /// Methods like [`Literal::subspan`] assume that the span covers exactly
+    /// the text of the literal. This does not hold anymore after giving a
+    /// literal the span of another token with `set_span`. In that case, this
+    /// method replaces the span by one pointing into a new, synthetic source
+    /// file containing just the literal. Spans which already point at the
+    /// literal's text are left untouched.
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn recompute_span(&mut self) {
+        self.0.span = self.0.span.with_source_text(&self.to_string());
+    }
+
+    /// Returns a span covering the digits of an integer literal, leaving out
+    /// a `0x`, `0o` or `0b` radix prefix and a type suffix, e.g. `DEAD` in
+    /// `0xDEADu32`.
+    ///
+    /// Unsuffixed decimal integers get their full span back. Returns `None`
+    /// if this is not an integer literal, or if the digits cannot be located,
+    /// in the same cases as [`Literal::subspan`].
+    #[unstable(feature = "proc_macro_span", issue = "54725")]
+    pub fn digits_span(&self) -> Option<Span> {
+        if self.0.kind != bridge::LitKind::Integer {
+            return None;
+        }
+        // The suffix is not part of the symbol, so the digits end with it.
+        let (start, end) = self.0.symbol.with(|symbol| {
+            let sign_len = if symbol.starts_with('-') { 1 } else { 0 };
+            let start = match symbol[sign_len..].as_bytes() {
+                [b'0', b'x' | b'o' | b'b', ..] => sign_len + 2,
+                _ => 0,
+            };
+            (start, symbol.len())
+        });
+        if start == 0 && self.0.suffix.is_none() {
+            return Some(self.span());
+        }
+        self.subspan(start..end)
+    }
+
+    /// Checks that the escapes in a character, byte, or string literal are
+    /// valid for its kind, e.g. that a non-byte string does not contain `\xFF`.
+    ///
+    /// Returns a message describing the first invalid escape otherwise, which
+    /// lets a macro report it on a span of its choosing instead of emitting a
+    /// literal that the compiler rejects later. Numeric literals are always
+    /// considered valid.
+    #[unstable(feature = "proc_macro_literal_validate", issue = "none")]
+    pub fn validate(&self) -> Result<(), String> {
+        bridge::client::FreeFunctions::validate_literal(self.0.clone())
+    }
+
+    /// Creates a literal of the given kind with the same contents, suffix and
+    /// span as this one, e.g. the integer `42` from the string `"42"`, or the
+    /// string `"a"` from the character `'a'`.
+    ///
+    /// Returns an error if the contents are not valid for the new kind, like
+    /// `"4 2"` for an integer or `"ab"` for a character, including invalid
+    /// escapes as reported by `validate`. Whether the suffix is valid for the
+    /// new kind is not checked.
+    #[unstable(feature = "proc_macro_literal_reinterpret", issue = "none")]
+    pub fn reinterpret(&self, kind: LiteralKind) -> Result<Literal, String> {
+        let literal = Literal(bridge::Literal { kind: kind.to_bridge(), ..self.0.clone() });
+        // Parsing a literal with invalid escapes reports them as errors, so
+        // they have to be ruled out first.
+        literal.validate()?;
+        let repr = literal.to_string();
+        match Literal::from_str(&repr) {
+            Ok(parsed) if parsed.0.kind == literal.0.kind => {}
+            _ => return Err(format!("`{repr}` is not a valid {kind:?} literal")),
+        }
+        Ok(literal)
+    }
+
     fn with_symbol_and_suffix<R>(&self, f: impl FnOnce(&str, &str) -> R) -> R {
         self.0.symbol.with(|symbol| match self.0.suffix {
             Some(suffix) => suffix.with(|suffix| f(symbol, suffix)),
@@ -9,6 +9,7 @@
 //! FIXME: No span and source file information is implemented yet
 
 use proc_macro::bridge::{self, server};
+use proc_macro::{LineColumn, PortableSpan};
 
 mod token_stream;
 use proc_macro_api::msg::TokenId;
@@ -19,6 +20,7 @@
 pub use symbol::*;
 
 use std::{
+    cmp::Ordering,
     iter,
     ops::{Bound, Range},
 };
@@ -84,6 +86,85 @@ fn literal_from_str(
     fn emit_diagnostic(&mut self, _: bridge::Diagnostic<Self::Span>) {
         // FIXME handle diagnostic
     }
+
+    fn emit_diagnostics(&mut self, _: Vec<bridge::Diagnostic<Self::Span>>) {
+        // FIXME handle diagnostics
+    }
+
+    fn emit_diagnostic_deduplicated(&mut self, _: bridge::Diagnostic<Self::Span>) {
+        // FIXME handle diagnostic
+    }
+
+    fn derive_siblings(&mut self) -> Vec<String> {
+        // FIXME: track the derives of the current `#[derive]` attribute
+        Vec::new()
+    }
+
+    fn invocation_args_span(&mut self) -> Self::Span {
+        // FIXME: compute the span of the macro input
+        self.call_site
+    }
+
+    fn validate_literal(
+        &mut self,
+        _literal: bridge::Literal<Self::Span, Self::Symbol>,
+    ) -> Result<(), String> {
+        // FIXME: unescape the literal and report invalid escapes
+        Ok(())
+    }
+
+    fn unescape_literal(
+        &mut self,
+        _literal: bridge::Literal<Self::Span, Self::Symbol>,
+    ) -> Option<Vec<u8>> {
+        // FIXME: unescape the literal
+        None
+    }
+
+    fn is_proc_macro_crate(&mut self) -> bool {
+        // FIXME: look up the crate type of the crate being expanded
+        false
+    }
+
+    fn feature_enabled(&mut self, _feature: &str) -> bool {
+        // FIXME: look up the features enabled in the crate being expanded
+        false
+    }
+
+    fn macro_crate_name(&mut self) -> String {
+        // FIXME: look up the name of the proc-macro crate
+        String::new()
+    }
+
+    fn input_token_count(&mut self) -> usize {
+        // FIXME: count the tokens of the macro input
+        0
+    }
+
+    fn enclosing_impl_span(&mut self) -> Option<Self::Span> {
+        // FIXME: track the `impl` block enclosing the macro call
+        None
+    }
+
+    fn enclosing_impl_self_ty(&mut self) -> Option<Self::TokenStream> {
+        // FIXME: track the `impl` block enclosing the macro call
+        None
+    }
+
+    fn compiler_channel(&mut self) -> proc_macro::CompilerChannel {
+        // FIXME: look up the channel of the toolchain used for the crate
+        proc_macro::CompilerChannel::Dev
+    }
+
+    fn target_pointer_width(&mut self) -> u32 {
+        // FIXME: look up the target of the crate being expanded
+        usize::BITS
+    }
+
+    fn out_dir(&mut self) -> Option<String> {
+        // rust-analyzer does not produce any output
+        None
+    }
 }
 
 impl server::TokenStream for RustAnalyzer {
@@ -93,9 +174,28 @@ fn is_empty(&mut self, stream: &Self::TokenStream) -> bool {
     fn from_str(&mut self, src: &str) -> Self::TokenStream {
         Self::TokenStream::from_str(src, self.call_site).expect("cannot parse string")
     }
+    fn type_path(&mut self, path: &str) -> Result<Self::TokenStream, String> {
+        // FIXME: check that `path` is a type path
+        Self::TokenStream::from_str(path, self.call_site)
+    }
     fn to_string(&mut self, stream: &Self::TokenStream) -> String {
         stream.to_string()
     }
+    fn to_string_formatted(&mut self, stream: &Self::TokenStream, _minify: bool) -> String {
+        // FIXME: honor `minify`
+        stream.to_string()
+    }
+    fn contains_ident(&mut self, stream: &Self::TokenStream, name: &str, is_raw: bool) -> bool {
+        fn contains(trees: &[TokenTree], text: &str) -> bool {
+            trees.iter().any(|tree| match tree {
+                tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => ident.text == text,
+                tt::TokenTree::Leaf(_) => false,
+                tt::TokenTree::Subtree(subtree) => contains(&subtree.token_trees, text),
+            })
+        }
+        let text = if is_raw { format!("r#{name}") } else { name.to_owned() };
+        contains(&stream.token_trees, &text)
+    }
     fn from_token_tree(
         &mut self,
         tree: bridge::TokenTree<Self::TokenStream, Self::Span, Self::Symbol>,
@@ -221,10 +321,79 @@ fn into_trees(
                         Some(subtree.token_trees.into_iter().collect())
                     },
                     span: bridge::DelimSpan::from_single(subtree.delimiter.open),
+                    interpolated: false,
                 }),
             })
             .collect()
     }
+
+    fn unwrap_single(&mut self, stream: Self::TokenStream) -> Self::TokenStream {
+        match &*stream.token_trees {
+            [tt::TokenTree::Subtree(subtree)]
+                if subtree.delimiter.kind == tt::DelimiterKind::Invisible =>
+            {
+                self.unwrap_single(TokenStream::with_subtree(subtree.clone()))
+            }
+            _ => stream,
+        }
+    }
+
+    fn diff(
+        &mut self,
+        _old: Option<Self::TokenStream>,
+        _new: Option<Self::TokenStream>,
+    ) -> Vec<bridge::TokenEdit> {
+        // FIXME: diff the flattened token trees
+        Vec::new()
+    }
+
+    fn repeat(
+        &mut self,
+        template: Option<Self::TokenStream>,
+        count: usize,
+        separator: Option<bridge::TokenTree<Self::TokenStream, Self::Span, Self::Symbol>>,
+    ) -> Self::TokenStream {
+        let template = template.unwrap_or_default();
+        let separator = separator.map(|tree| self.from_token_tree(tree));
+        let mut builder = TokenStreamBuilder::new();
+        for i in 0..count {
+            if let Some(separator) = separator.as_ref().filter(|_| i > 0) {
+                builder.push(separator.clone());
+            }
+            builder.push(template.clone());
+        }
+        builder.build()
+    }
+
+    fn first_kind(&mut self, stream: &Self::TokenStream) -> Option<u8> {
+        let kind = match stream.token_trees.first()? {
+            tt::TokenTree::Subtree(_) => 0,
+            tt::TokenTree::Leaf(tt::Leaf::Ident(_)) => 1,
+            tt::TokenTree::Leaf(tt::Leaf::Punct(_)) => 2,
+            tt::TokenTree::Leaf(tt::Leaf::Literal(_)) => 3,
+        };
+        Some(kind)
+    }
+
+    fn validate_as_item(&mut self, _stream: &Self::TokenStream) -> Result<(), String> {
+        // FIXME: parse the stream as items
+        Ok(())
+    }
+
+    fn validate_as_expr(&mut self, _stream: &Self::TokenStream) -> Result<(), String> {
+        // FIXME: parse the stream as an expression
+        Ok(())
+    }
+
+    fn nth_span(&mut self, stream: &Self::TokenStream, n: usize) -> Option<Self::Span> {
+        let span = match stream.token_trees.get(n)? {
+            tt::TokenTree::Leaf(tt::Leaf::Ident(ident)) => ident.span,
+            tt::TokenTree::Leaf(tt::Leaf::Literal(lit)) => lit.span,
+            tt::TokenTree::Leaf(tt::Leaf::Punct(punct)) => punct.span,
+            tt::TokenTree::Subtree(subtree) => subtree.delimiter.open,
+        };
+        Some(span)
+    }
 }
 
 fn delim_to_internal(d: proc_macro::Delimiter, span: bridge::DelimSpan<Span>) -> tt::Delimiter {
@@ -273,6 +442,18 @@ fn path(&mut self, _file: &Self::SourceFile) -> String {
     fn is_real(&mut self, _file: &Self::SourceFile) -> bool {
         true
     }
+    fn source_file_at(&mut self, _pos: u32) -> Option<Self::SourceFile> {
+        None
+    }
+    fn register_virtual(&mut self, _name: &str, _contents: String) -> Self::SourceFile {
+        SourceFile {}
+    }
+    fn span(&mut self, _file: &Self::SourceFile, _range: Range<usize>) -> Option<Self::Span> {
+        None
+    }
+    fn path_cmp(&mut self, _file: &Self::SourceFile, _other: &Self::SourceFile) -> Ordering {
+        Ordering::Equal
+    }
 }
 
 impl server::Span for RustAnalyzer {
@@ -345,6 +526,92 @@ fn column(&mut self, _span: Self::Span) -> usize {
         // FIXME handle column
         0
     }
+
+    fn is_generated(&mut self, _span: Self::Span) -> bool {
+        // FIXME handle hygiene
+        false
+    }
+
+    fn expansion_name(&mut self, _span: Self::Span) -> Option<String> {
+        // FIXME handle span
+        None
+    }
+
+    fn truncate(&mut self, span: Self::Span, _max_bytes: usize) -> Self::Span {
+        // FIXME handle span
+        span
+    }
+
+    fn contains(&mut self, span: Self::Span, other: Self::Span) -> bool {
+        // FIXME handle span
+        span == other
+    }
+
+    fn with_source_text(&mut self, span: Self::Span, _text: &str) -> Self::Span {
+        // FIXME handle span
+        span
+    }
+
+    fn locations(&mut self, spans: Vec<Self::Span>) -> Vec<Range<LineColumn>> {
+        // FIXME handle line and column
+        let location = LineColumn { line: 0, column: 0 };
+        vec![location..location; spans.len()]
+    }
+
+    fn caller_location(&mut self, _span: Self::Span) -> bridge::CallerLocation {
+        // FIXME: resolve the span to its file, line and column
+        bridge::CallerLocation { file: String::new(), line: 1, column: 1 }
+    }
+
+    fn source_cmp(&mut self, span: Self::Span, other: Self::Span) -> Ordering {
+        // FIXME handle span, token ids are not assigned in source order
+        span.0.cmp(&other.0)
+    }
+
+    fn line_text(&mut self, _span: Self::Span) -> Option<String> {
+        None
+    }
+
+    fn point(&mut self, span: Self::Span) -> Self::Span {
+        // FIXME handle span
+        span
+    }
+
+    fn extend_to_next_token(&mut self, span: Self::Span) -> Self::Span {
+        // FIXME handle span
+        span
+    }
+
+    fn to_end_of_line(&mut self, span: Self::Span) -> Self::Span {
+        // FIXME handle span
+        span
+    }
+
+    fn same_expansion(&mut self, _span: Self::Span, _other: Self::Span) -> bool {
+        // FIXME handle span
+        true
+    }
+
+    fn binds_like_call_site(&mut self, _span: Self::Span) -> bool {
+        // FIXME handle hygiene
+        true
+    }
+
+    fn is_dummy(&mut self, _span: Self::Span) -> bool {
+        // FIXME handle span
+        false
+    }
+
+    fn to_portable(&mut self, _span: Self::Span) -> PortableSpan {
+        // FIXME: resolve the span to its file, line and column
+        let start = LineColumn { line: 1, column: 1 };
+        PortableSpan { file_path: String::new(), start, end: start }
+    }
+
+    fn is_included(&mut self, _span: Self::Span) -> bool {
+        // FIXME handle span
+        false
+    }
 }
 
 impl server::Symbol for RustAnalyzer {
@@ -352,6 +619,11 @@ fn normalize_and_validate_ident(&mut self, string: &str) -> Result<Self::Symbol,
         // FIXME: nfc-normalize and validate idents
         Ok(<Self as server::Server>::intern_symbol(string))
     }
+
+    fn is_reserved(&mut self, _string: &str, _span: Self::Span) -> bool {
+        // FIXME: check for keywords, taking the span's edition into account
+        false
+    }
 }
 
 impl server::Server for RustAnalyzer {
@@ -0,0 +1,14 @@
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_out_dir)]
+
+extern crate proc_macro;
+
+use proc_macro::{Literal, TokenStream};
+
+#[proc_macro]
+pub fn assert_out_dir(input: TokenStream) -> TokenStream {
+    let expected: Literal = input.to_string().parse().unwrap();
+    let out_dir = proc_macro::out_dir().expect("no output directory");
+    assert_eq!(Literal::string(&out_dir).to_string(), expected.to_string());
+    TokenStream::new()
+}
@@ -0,0 +1,8 @@
+#![crate_type = "lib"]
+
+extern crate out_dir;
+
+#[cfg(cwd)]
+out_dir::assert_out_dir!(".");
+#[cfg(sub)]
+out_dir::assert_out_dir!("sub");
@@ -3,14 +3,40 @@
 
 #![crate_type = "proc-macro"]
 #![crate_name = "proc_macro_api_tests"]
+#![feature(proc_macro_closure)]
+#![feature(proc_macro_const_generic_arg)]
+#![feature(proc_macro_diff)]
+#![feature(proc_macro_field_access)]
+#![feature(proc_macro_first_kind)]
+#![feature(proc_macro_formatting)]
+#![feature(proc_macro_generic_params)]
+#![feature(proc_macro_if_expr)]
+#![feature(proc_macro_literal_reinterpret)]
+#![feature(proc_macro_literal_validate)]
+#![feature(proc_macro_match_expr)]
+#![feature(proc_macro_nth_token_span)]
+#![feature(proc_macro_qualified_call)]
 #![feature(proc_macro_span)]
 #![feature(proc_macro_byte_character)]
+#![feature(proc_macro_escape_for_string)]
+#![feature(proc_macro_float_suffixed)]
+#![feature(proc_macro_float_value)]
+#![feature(proc_macro_lifetime)]
+#![feature(proc_macro_literal_as_const)]
+#![feature(proc_macro_numeric_suffix)]
+#![feature(proc_macro_reverse_top_level)]
+#![feature(proc_macro_strip_attributes)]
+#![feature(proc_macro_template)]
+#![feature(proc_macro_validate_as_item)]
+#![feature(proc_macro_where_clause)]
 #![deny(dead_code)] // catch if a test function is never called
 
 extern crate proc_macro;
 
 mod cmp;
 mod parse;
+mod span;
+mod stream;
 
 use proc_macro::TokenStream;
 
@@ -20,6 +46,8 @@ pub fn run(input: TokenStream) -> TokenStream {
 
     cmp::test();
     parse::test();
+    span::test();
+    stream::test();
 
     TokenStream::new()
 }
@@ -1,15 +1,29 @@
 // ignore-tidy-linelength
 
-use proc_macro::Literal;
+use proc_macro::{
+    ConstValue, FloatType, GenericParamKind, GenericParamSpec, Ident, Literal, LiteralKind,
+    NumericType, Span, TokenStream, TokenTree,
+};
 
 pub fn test() {
     test_display_literal();
     test_parse_literal();
+    test_lifetime();
+    test_template();
+    test_signed_zero();
+    test_float_suffixed();
+    test_digit_separators();
+    test_reverse_top_level();
+    test_reinterpret();
+    test_validate_literal();
+    test_generic_params();
 }
 
 fn test_display_literal() {
     assert_eq!(Literal::isize_unsuffixed(-10).to_string(), "-10");
     assert_eq!(Literal::isize_suffixed(-10).to_string(), "-10isize");
+    assert_eq!(Literal::usize_suffixed(10).to_string(), "10usize");
+    assert_eq!(Literal::usize_unsuffixed(10).to_string(), "10");
     assert_eq!(Literal::f32_unsuffixed(-10.0).to_string(), "-10.0");
     assert_eq!(Literal::f32_suffixed(-10.0).to_string(), "-10f32");
     assert_eq!(Literal::f64_unsuffixed(-10.0).to_string(), "-10.0");
@@ -23,6 +37,7 @@ fn test_display_literal() {
         Literal::string("a \t ❤ ' \" \u{1}").to_string(),
         "\"a \\t ❤ ' \\\" \\u{1}\"",
     );
+    assert_eq!(Literal::escape_for_string("a \t ❤ ' \" \u{1}"), "a \\t ❤ ' \\\" \\u{1}");
     assert_eq!(Literal::character('a').to_string(), "'a'");
     assert_eq!(Literal::character('\t').to_string(), "'\\t'");
     assert_eq!(Literal::character('❤').to_string(), "'❤'");
@@ -57,3 +72,138 @@ fn test_parse_literal() {
     assert!("- 10".parse::<Literal>().is_err());
     assert!("-'x'".parse::<Literal>().is_err());
 }
+
+fn test_lifetime() {
+    let span = Span::call_site();
+    assert_eq!(TokenStream::lifetime("a", span).unwrap().to_string(), "'a");
+    assert_eq!(TokenStream::lifetime("'static", span).unwrap().to_string(), "'static");
+    assert_eq!(TokenStream::lifetime("_", span).unwrap().to_string(), "'_");
+    assert!(TokenStream::lifetime("fn", span).is_err());
+    assert!(TokenStream::lifetime("1a", span).is_err());
+    assert!(TokenStream::lifetime("", span).is_err());
+}
+
+fn test_template() {
+    let name: TokenStream = "foo".parse().unwrap();
+    let body: TokenStream = "1 + 2".parse().unwrap();
+    let stream = TokenStream::template("fn {}() -> u32 {{ {} }}", &[name, body]).unwrap();
+    let trees: Vec<TokenTree> = stream.into_iter().collect();
+    assert_eq!(trees.len(), 7);
+    assert_eq!(trees[1].to_string(), "foo");
+    match &trees[6] {
+        TokenTree::Group(group) => assert_eq!(group.stream().to_string(), "1 + 2"),
+        _ => panic!("expected a group"),
+    }
+
+    assert!(TokenStream::template("{} {}", &[TokenStream::new()]).is_err());
+    assert!(TokenStream::template("fn f() {}", &[]).is_err());
+    assert!(TokenStream::template("fn f() { 0", &[]).is_err());
+}
+
+fn test_signed_zero() {
+    assert_eq!(Literal::f32_unsuffixed(-0.0).to_string(), "-0.0");
+    assert_eq!(Literal::f32_suffixed(-0.0).to_string(), "-0f32");
+    assert_eq!(Literal::f64_unsuffixed(-0.0).to_string(), "-0.0");
+    assert_eq!(Literal::f64_suffixed(-0.0).to_string(), "-0f64");
+
+    for lit in [Literal::f64_unsuffixed(-0.0), Literal::f64_suffixed(-0.0)] {
+        let reparsed: Literal = lit.to_string().parse().unwrap();
+        let value = reparsed.float_value().unwrap();
+        assert!(value == 0.0 && value.is_sign_negative());
+    }
+    let value = Literal::f64_unsuffixed(0.0).float_value().unwrap();
+    assert!(value == 0.0 && value.is_sign_positive());
+}
+
+fn test_float_suffixed() {
+    assert_eq!(Literal::float_suffixed(1.5, FloatType::F32).to_string(), "1.5f32");
+    assert_eq!(Literal::float_suffixed(0.1, FloatType::F32).to_string(), "0.1f32");
+    assert_eq!(Literal::float_suffixed(0.1, FloatType::F64).to_string(), "0.1f64");
+
+    for n in [0.1, 1.0 / 3.0, 1e-7, 123456.789] {
+        let lit: Literal = Literal::float_suffixed(n, FloatType::F32).to_string().parse().unwrap();
+        assert_eq!(lit.float_value().unwrap() as f32, n as f32);
+        let lit: Literal = Literal::float_suffixed(n, FloatType::F64).to_string().parse().unwrap();
+        assert_eq!(lit.float_value().unwrap(), n);
+    }
+}
+
+fn test_digit_separators() {
+    let lit: Literal = "0xDE_AD_BE_EF".parse().unwrap();
+    assert_eq!(lit.as_const(), Some(ConstValue::Int(0xDEAD_BEEF, None)));
+    let lit: Literal = "1_000_000u32".parse().unwrap();
+    assert_eq!(lit.as_const(), Some(ConstValue::Int(1_000_000, Some(NumericType::U32))));
+    let lit: Literal = "1_000.000_1".parse().unwrap();
+    assert_eq!(lit.float_value(), Some(1_000.000_1));
+    assert_eq!(lit.as_const(), Some(ConstValue::Float(1_000.000_1, None)));
+}
+
+fn test_reverse_top_level() {
+    let stream: TokenStream = "a b c".parse().unwrap();
+    assert_eq!(stream.reverse_top_level().to_string(), "c b a");
+    let stream: TokenStream = "x += (1, 2)".parse().unwrap();
+    assert_eq!(stream.reverse_top_level().to_string(), "(1, 2) = + x");
+    assert!(TokenStream::new().reverse_top_level().is_empty());
+}
+
+fn test_reinterpret() {
+    let string: Literal = "\"42\"".parse().unwrap();
+    assert_eq!(string.reinterpret(LiteralKind::Integer).unwrap().to_string(), "42");
+    assert_eq!(string.reinterpret(LiteralKind::StrRaw(1)).unwrap().to_string(), "r#\"42\"#");
+    let character: Literal = "'a'".parse().unwrap();
+    assert_eq!(character.reinterpret(LiteralKind::Str).unwrap().to_string(), "\"a\"");
+
+    let string: Literal = "\"4 2\"".parse().unwrap();
+    assert_eq!(
+        string.reinterpret(LiteralKind::Integer).unwrap_err(),
+        "`4 2` is not a valid Integer literal",
+    );
+    let string: Literal = "\"ab\"".parse().unwrap();
+    assert!(string.reinterpret(LiteralKind::Char).is_err());
+    let raw: Literal = "r#\"a\"b\"#".parse().unwrap();
+    assert!(raw.reinterpret(LiteralKind::Str).is_err());
+}
+
+fn test_validate_literal() {
+    for valid in ["\"a\\n\\u{2764}\"", "b\"\\xFF\"", "r\"\\q\"", "'\\''", "1u8"] {
+        assert_eq!(valid.parse::<Literal>().unwrap().validate(), Ok(()), "{valid}");
+    }
+
+    // Parsing reports invalid escapes as errors right away, so they are
+    // checked through `reinterpret`, which validates the raw contents.
+    let invalid = |raw: &str, kind| {
+        let literal: Literal = format!("r\"{raw}\"").parse().unwrap();
+        literal.reinterpret(kind).unwrap_err()
+    };
+    assert_eq!(invalid("\\q", LiteralKind::Str), "unknown character escape: `\\q`");
+    assert_eq!(invalid("a\\u{12", LiteralKind::Str), "unterminated unicode escape: `\\u{12`");
+    assert_eq!(invalid("\\xFF", LiteralKind::Str), "out of range hex escape: `\\xFF`");
+    assert_eq!(invalid("é", LiteralKind::ByteStr), "non-ASCII character in byte literal: `é`");
+    assert_eq!(invalid("\\u{0}", LiteralKind::ByteStr), "unicode escape in byte string: `\\u{0}`");
+}
+
+fn test_generic_params() {
+    let span = Span::call_site();
+    let parse = |source: &str| source.parse::<TokenStream>().unwrap();
+    let param = |kind, name, bounds: Option<&str>, default: Option<&str>| GenericParamSpec {
+        kind,
+        name: Ident::new(name, span),
+        bounds: bounds.map(parse),
+        default: default.map(parse),
+    };
+    let params = vec![
+        param(GenericParamKind::Lifetime, "a", Some("'b"), None),
+        param(GenericParamKind::Lifetime, "b", None, None),
+        param(GenericParamKind::Type, "T", Some("Clone + 'a"), Some("String")),
+        param(GenericParamKind::Type, "U", None, None),
+        param(GenericParamKind::Const, "N", Some("usize"), None),
+        param(GenericParamKind::Const, "M", Some("usize"), Some("N * 2")),
+    ];
+    let expected =
+        "<'a: 'b, 'b, T: Clone + 'a = String, U, const N: usize, const M: usize = { N * 2 }>";
+    assert_eq!(
+        TokenStream::generic_params(params, span).canonical_string(),
+        parse(expected).canonical_string(),
+    );
+    assert!(TokenStream::generic_params(Vec::new(), span).is_empty());
+}
@@ -0,0 +1,90 @@
+use proc_macro::{LineColumn, Literal, Span, TokenStream};
+
+pub fn test() {
+    test_point();
+    test_locations();
+    test_start();
+    test_digits_span();
+    test_string_content_span();
+}
+
+fn test_point() {
+    // The call site is the `proc_macro_api_tests::run!()` invocation.
+    let span = Span::call_site();
+    assert_eq!(span.point().source_text().as_deref(), Some("p"));
+    assert_eq!(span.point().start().source_text().as_deref(), Some(""));
+    assert_eq!(span.start().point().source_text().as_deref(), Some("p"));
+}
+
+fn test_locations() {
+    let first: TokenStream = "αβ x\n  \"δ\nεζ\" y".parse().unwrap();
+    let second: TokenStream = "ü".parse().unwrap();
+    let mut spans: Vec<Span> = first.into_iter().map(|tree| tree.span()).collect();
+    spans.extend(second.into_iter().map(|tree| tree.span()));
+    let location = |line, column| LineColumn { line, column };
+    let expected = [
+        (location(1, 1), location(1, 3)),
+        (location(1, 4), location(1, 5)),
+        (location(2, 3), location(3, 4)),
+        (location(3, 5), location(3, 6)),
+        (location(1, 1), location(1, 2)),
+    ];
+    assert_eq!(Span::locations(&spans), expected);
+    for (span, (start, end)) in spans.iter().zip(expected) {
+        assert_eq!((span.line(), span.column()), (start.line, start.column));
+        assert_eq!((span.end().line(), span.end().column()), (end.line, end.column));
+    }
+
+    // Spans don't have to be sorted, nor grouped by file.
+    let order = [4, 2, 0, 3, 1, 4];
+    let unsorted: Vec<Span> = order.iter().map(|&i| spans[i]).collect();
+    let expected_unsorted: Vec<_> = order.iter().map(|&i| expected[i]).collect();
+    assert_eq!(Span::locations(&unsorted), expected_unsorted);
+    assert!(Span::locations(&[]).is_empty());
+}
+
+fn test_start() {
+    let stream: TokenStream = "let x = 1;".parse().unwrap();
+    let name = stream.into_iter().nth(1).unwrap().span();
+    let start = name.start();
+    assert_eq!(start.source_text().as_deref(), Some(""));
+    assert_eq!(start.byte_range(), 4..4);
+    assert_eq!(start.join(name).unwrap().source_text().as_deref(), Some("x"));
+    assert_eq!(name.end().byte_range(), 5..5);
+}
+
+fn test_digits_span() {
+    let digits = |source: &str| {
+        let literal: Literal = source.parse().unwrap();
+        literal.digits_span().map(|span| span.source_text().unwrap())
+    };
+    assert_eq!(digits("0xDEAD").as_deref(), Some("DEAD"));
+    assert_eq!(digits("0xDEADu32").as_deref(), Some("DEAD"));
+    assert_eq!(digits("0o17").as_deref(), Some("17"));
+    assert_eq!(digits("0o17i8").as_deref(), Some("17"));
+    assert_eq!(digits("0b1010").as_deref(), Some("1010"));
+    assert_eq!(digits("0b10_10usize").as_deref(), Some("10_10"));
+    assert_eq!(digits("42").as_deref(), Some("42"));
+    assert_eq!(digits("42u8").as_deref(), Some("42"));
+    assert_eq!(digits("1.0"), None);
+    assert_eq!(digits("\"0x1\""), None);
+}
+
+fn test_string_content_span() {
+    let content = |source: &str| {
+        let literal: Literal = source.parse().unwrap();
+        literal.string_content_span().map(|span| span.source_text().unwrap())
+    };
+    assert_eq!(content("\"abc\"").as_deref(), Some("abc"));
+    assert_eq!(content("\"a\\nc\"").as_deref(), Some("a\\nc"));
+    assert_eq!(content("r\"abc\"").as_deref(), Some("abc"));
+    assert_eq!(content("r#\"abc\"#").as_deref(), Some("abc"));
+    assert_eq!(content("b\"abc\"").as_deref(), Some("abc"));
+    assert_eq!(content("br##\"abc\"##").as_deref(), Some("abc"));
+    assert_eq!(content("c\"abc\"").as_deref(), Some("abc"));
+    assert_eq!(content("cr#\"abc\"#").as_deref(), Some("abc"));
+    assert_eq!(content("\"abc\"suffix").as_deref(), Some("abc"));
+    assert_eq!(content("r#\"abc\"#suffix").as_deref(), Some("abc"));
+    assert_eq!(content("'a'"), None);
+    assert_eq!(content("42"), None);
+}
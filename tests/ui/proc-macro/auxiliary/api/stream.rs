@@ -0,0 +1,247 @@
+use proc_macro::{FieldAccess, Ident, Span, TokenDiff, TokenStream, TokenTree, TokenTreeKind};
+
+pub fn test() {
+    test_minify();
+    test_canonical_string();
+    test_match_expr();
+    test_where_clause();
+    test_qualified_call();
+    test_diff();
+    test_nth_token_span();
+    test_first_kind();
+    test_strip_attributes();
+    test_if_expr();
+    test_closure();
+    test_const_generic_arg();
+    test_field_access();
+}
+
+fn test_minify() {
+    // Tokens are only separated where they would otherwise lex differently.
+    for (src, minified) in [
+        ("\"x\" as T", "\"x\" as T"),
+        ("'c' as T", "'c' as T"),
+        ("1 .max(2)", "1 .max(2)"),
+        ("a += b", "a+=b"),
+    ] {
+        let stream: TokenStream = src.parse().unwrap();
+        assert_eq!(stream.to_string_formatted(true), minified);
+        let reparsed: TokenStream = minified.parse().unwrap();
+        assert_eq!(reparsed.to_string(), stream.to_string());
+    }
+}
+
+fn test_canonical_string() {
+    let spaced: TokenStream = "f( a ,b )".parse().unwrap();
+    let tight: TokenStream = "f(a, b)".parse().unwrap();
+    assert_eq!(spaced.canonical_string(), tight.canonical_string());
+
+    // A literal followed by an identifier is different from a suffixed literal.
+    let cast: TokenStream = "\"x\" as".parse().unwrap();
+    let suffixed: TokenStream = "\"x\"as".parse().unwrap();
+    assert_eq!(suffixed.clone().into_iter().count(), 1);
+    assert_ne!(cast.canonical_string(), suffixed.canonical_string());
+}
+
+fn test_match_expr() {
+    let span = Span::call_site();
+    let arms = vec![
+        ("Some(x)".parse().unwrap(), "x".parse().unwrap()),
+        ("None".parse().unwrap(), "0".parse().unwrap()),
+    ];
+    let expr = TokenStream::match_expr("opt".parse().unwrap(), arms, span).unwrap();
+    let expected: TokenStream = "match opt { Some(x) => { x }, None => { 0 }, }".parse().unwrap();
+    assert_eq!(expr.to_string(), expected.to_string());
+
+    let arms = vec![
+        ("_".parse().unwrap(), "1".parse().unwrap()),
+        ("_".parse().unwrap(), "let".parse().unwrap()),
+    ];
+    let err = TokenStream::match_expr("opt".parse().unwrap(), arms, span).unwrap_err();
+    assert!(err.starts_with("arm 1: "), "{err}");
+    let arms = vec![("=>".parse().unwrap(), "1".parse().unwrap())];
+    assert!(TokenStream::match_expr("opt".parse().unwrap(), arms, span).is_err());
+}
+
+fn test_where_clause() {
+    let span = Span::call_site();
+    assert!(TokenStream::where_clause(vec![], span).unwrap().is_empty());
+    let bounds = vec![
+        ("T".parse().unwrap(), "Clone".parse().unwrap()),
+        ("U".parse().unwrap(), "Debug + Send".parse().unwrap()),
+    ];
+    let clause = TokenStream::where_clause(bounds, span).unwrap();
+    let expected: TokenStream = "where T: Clone, U: Debug + Send".parse().unwrap();
+    assert_eq!(clause.to_string(), expected.to_string());
+
+    let bounds = vec![
+        ("T".parse().unwrap(), "Clone".parse().unwrap()),
+        ("U".parse().unwrap(), "Clone Copy".parse().unwrap()),
+    ];
+    let err = TokenStream::where_clause(bounds, span).unwrap_err();
+    assert!(err.starts_with("predicate 1: "), "{err}");
+    let bounds = vec![("1".parse().unwrap(), "Clone".parse().unwrap())];
+    assert!(TokenStream::where_clause(bounds, span).is_err());
+}
+
+fn test_qualified_call() {
+    let span = Span::call_site();
+    let call = |ty: &str, trait_path: &str, args: &[&str]| {
+        let (ty, trait_path) = (ty.parse().unwrap(), trait_path.parse().unwrap());
+        let args = args.iter().map(|arg| arg.parse().unwrap()).collect();
+        TokenStream::qualified_call(ty, trait_path, Ident::new("from", span), args, span)
+    };
+    let expected: TokenStream = "<Vec<u8> as From<&str>>::from(\"x\")".parse().unwrap();
+    let result = call("Vec<u8>", "From<&str>", &["\"x\""]).unwrap();
+    assert_eq!(result.to_string(), expected.to_string());
+
+    let err = call("1", "From<&str>", &[]).unwrap_err();
+    assert!(err.starts_with("invalid qualified path: "), "{err}");
+    let err = call("Vec<u8>", "&From<&str>", &[]).unwrap_err();
+    assert!(err.starts_with("invalid qualified path: "), "{err}");
+    let err = call("Vec<u8>", "From<&str>", &["x", "let"]).unwrap_err();
+    assert!(err.starts_with("argument 1: "), "{err}");
+}
+
+fn test_diff() {
+    let diff = |old: &str, new: &str| {
+        let (old, new): (TokenStream, TokenStream) = (old.parse().unwrap(), new.parse().unwrap());
+        old.diff(&new)
+    };
+    let insert = |index, token: &str| TokenDiff::Insert { index, token: token.to_string() };
+    let delete = |index, token: &str| TokenDiff::Delete { index, token: token.to_string() };
+
+    assert!(diff("", "").is_empty());
+    assert!(diff("f(a, b)", "f(a, b)").is_empty());
+    assert_eq!(diff("", "a b"), [insert(0, "a"), insert(1, "b")]);
+    assert_eq!(diff("a b", ""), [delete(0, "a"), delete(1, "b")]);
+    // Groups are flattened into their delimiters and contents.
+    assert_eq!(diff("f(a)", "f(a, b)"), [insert(3, ","), insert(4, "b")]);
+    assert_eq!(diff("f(a, b)", "f(b)"), [delete(2, "a"), delete(3, ",")]);
+    assert_eq!(diff("a + b", "a - b"), [delete(1, "+"), insert(1, "-")]);
+    assert_eq!(
+        diff("a b c d e", "x a c e y"),
+        [insert(0, "x"), delete(1, "b"), delete(3, "d"), insert(4, "y")],
+    );
+}
+
+fn test_nth_token_span() {
+    let stream: TokenStream = "a += (b, c) 'd".parse().unwrap();
+    let trees: Vec<TokenTree> = stream.clone().into_iter().collect();
+    assert_eq!(trees.len(), 6);
+    for (n, tree) in trees.iter().enumerate() {
+        assert!(stream.nth_token_span(n).unwrap().eq(&tree.span()), "{n}");
+    }
+    assert!(stream.nth_token_span(6).is_none());
+    assert!(TokenStream::new().nth_token_span(0).is_none());
+}
+
+fn test_first_kind() {
+    let cases = [
+        ("(a) b", TokenTreeKind::Group),
+        ("a (b)", TokenTreeKind::Ident),
+        ("r#a", TokenTreeKind::Ident),
+        ("+= a", TokenTreeKind::Punct),
+        ("'a", TokenTreeKind::Punct),
+        ("/// a", TokenTreeKind::Punct),
+        ("1 a", TokenTreeKind::Literal),
+        ("\"a\"", TokenTreeKind::Literal),
+    ];
+    for (source, kind) in cases {
+        let stream: TokenStream = source.parse().unwrap();
+        assert_eq!(stream.first_kind(), Some(kind), "{source}");
+    }
+    assert_eq!(TokenStream::new().first_kind(), None);
+}
+
+fn test_strip_attributes() {
+    let check = |source: &str, expected: &str| {
+        let stripped = source.parse::<TokenStream>().unwrap().strip_attributes();
+        let expected: TokenStream = expected.parse().unwrap();
+        assert_eq!(stripped.canonical_string(), expected.canonical_string());
+    };
+    check("#[inline] /** Docs */ fn f() {}", "fn f() {}");
+    check("#![no_std] #[a] #[b(c)] struct S;", "struct S;");
+    // Only top-level attributes are removed.
+    check("struct S { #[a] x: u8 }", "struct S { #[a] x: u8 }");
+    // `#` and `!` are kept unless followed by brackets.
+    check("# (a) #! {b} a != c", "# (a) #! {b} a != c");
+    check("#[a]", "");
+}
+
+fn test_if_expr() {
+    let parse = |source: &str| source.parse::<TokenStream>().unwrap();
+    let check = |expr: TokenStream, expected: &str| {
+        assert_eq!(expr.canonical_string(), parse(expected).canonical_string());
+    };
+    let span = Span::call_site();
+    check(TokenStream::if_expr(parse("c"), parse("a"), None, span).unwrap(), "if c { a }");
+    // Blocks that already are braced are not wrapped again.
+    check(TokenStream::if_expr(parse("c"), parse("{ a }"), None, span).unwrap(), "if c { a }");
+    let else_if = TokenStream::if_expr(parse("d"), parse("b"), None, span).unwrap();
+    check(
+        TokenStream::if_expr(parse("c"), parse("{ a }"), Some(else_if), span).unwrap(),
+        "if c { a } else if d { b }",
+    );
+    check(
+        TokenStream::if_expr(parse("c"), parse("a"), Some(parse("{ b }")), span).unwrap(),
+        "if c { a } else { b }",
+    );
+    assert!(TokenStream::if_expr(parse("c"), parse("a"), Some(parse("b")), span).is_ok());
+    assert!(TokenStream::if_expr(parse("c c"), parse("a"), None, span).is_err());
+}
+
+fn test_closure() {
+    let parse = |source: &str| source.parse::<TokenStream>().unwrap();
+    let check = |expr: TokenStream, expected: &str| {
+        assert_eq!(expr.canonical_string(), parse(expected).canonical_string());
+    };
+    let span = Span::call_site();
+    let closure = |params: &[&str], body, move_kw| {
+        let params = params.iter().map(|&param| parse(param)).collect();
+        TokenStream::closure(params, parse(body), move_kw, span)
+    };
+    check(closure(&["x", "y: u32"], "x + y", false).unwrap(), "|x, y: u32| { x + y }");
+    // A body that already is a block is not wrapped again.
+    check(closure(&["(a, b)"], "{ a }", true).unwrap(), "move |(a, b)| { a }");
+    assert!(closure(&[], "1", false).is_ok());
+    assert!(closure(&["+"], "1", false).is_err());
+}
+
+fn test_const_generic_arg() {
+    let parse = |source: &str| source.parse::<TokenStream>().unwrap();
+    let check = |expr: TokenStream, expected: &str| {
+        assert_eq!(expr.canonical_string(), parse(expected).canonical_string());
+    };
+    let arg = |source: &str| TokenStream::const_generic_arg(parse(source), Span::call_site());
+    check(arg("3").unwrap(), "3");
+    check(arg("-1").unwrap(), "-1");
+    check(arg("{ N + 1 }").unwrap(), "{ N + 1 }");
+    check(arg("N + 1").unwrap(), "{ N + 1 }");
+    check(arg("N").unwrap(), "N");
+    // Only a single identifier can be told apart from a type.
+    check(arg("consts::N").unwrap(), "{ consts::N }");
+    check(arg("<T as Trait>::N").unwrap(), "{ <T as Trait>::N }");
+    assert!(arg("+").is_err());
+}
+
+fn test_field_access() {
+    let parse = |source: &str| source.parse::<TokenStream>().unwrap();
+    let check = |expr: TokenStream, expected: &str| {
+        assert_eq!(expr.canonical_string(), parse(expected).canonical_string());
+    };
+    let span = Span::call_site();
+    let access = |base, path: &[FieldAccess]| TokenStream::field_access(parse(base), path, span);
+    let inner = FieldAccess::Named(Ident::new("inner", span));
+    check(access("self", &[inner.clone()]), "self.inner");
+    check(access("self", &[inner.clone(), FieldAccess::Indexed(0)]), "self.inner.0");
+    check(access("Self::VALUE", &[inner.clone()]), "Self::VALUE.inner");
+    // Each index is a token of its own, not the float literal `0.1`.
+    let pair = access("pair", &[FieldAccess::Indexed(0), FieldAccess::Indexed(1)]);
+    assert_eq!(pair.clone().into_iter().count(), 5);
+    check(pair, "pair.0.1");
+    check(access("a + b", &[inner.clone()]), "(a + b).inner");
+    check(access("-1", &[FieldAccess::Indexed(0)]), "(-1).0");
+    check(access("(a, b)", &[FieldAccess::Indexed(1)]), "(a, b).1");
+    check(access("x", &[]), "x");
+}
@@ -0,0 +1,18 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_diagnostic)]
+
+extern crate proc_macro;
+
+use proc_macro::{Diagnostic, Level, Span, TokenStream};
+
+#[proc_macro]
+pub fn drop_diagnostic(_input: TokenStream) -> TokenStream {
+    let diagnostic = Diagnostic::spanned(Span::call_site(), Level::Error, "never emitted")
+        .span_warning(Span::call_site(), "child")
+        .note("note");
+    drop(diagnostic);
+    TokenStream::new()
+}
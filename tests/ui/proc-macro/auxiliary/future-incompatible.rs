@@ -0,0 +1,18 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_diagnostic, proc_macro_future_incompatible)]
+
+extern crate proc_macro;
+
+use proc_macro::{Diagnostic, Level, Span, TokenStream};
+
+#[proc_macro]
+pub fn old_syntax(input: TokenStream) -> TokenStream {
+    let span = input.into_iter().next().map_or_else(Span::call_site, |tree| tree.span());
+    Diagnostic::spanned(span, Level::Warning, "`old_syntax!` is deprecated")
+        .future_incompatible("my_tool", "old_syntax", "use `new_syntax!` instead")
+        .emit();
+    TokenStream::new()
+}
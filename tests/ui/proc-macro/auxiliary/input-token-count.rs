@@ -0,0 +1,19 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_input_token_count)]
+
+extern crate proc_macro;
+
+use proc_macro::{input_token_count, TokenStream};
+
+#[proc_macro]
+pub fn count(_input: TokenStream) -> TokenStream {
+    input_token_count().to_string().parse().unwrap()
+}
+
+#[proc_macro_attribute]
+pub fn count_attr(_args: TokenStream, item: TokenStream) -> TokenStream {
+    format!("const COUNT: usize = {}; {item}", input_token_count()).parse().unwrap()
+}
@@ -0,0 +1,23 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_diagnostic)]
+
+extern crate proc_macro;
+
+use proc_macro::{Diagnostic, Level, TokenStream, TokenTree};
+
+/// Checks that the binding in `let <name> = ...;` is mutable.
+#[proc_macro]
+pub fn require_mut(input: TokenStream) -> TokenStream {
+    let mut trees = input.into_iter().skip(1);
+    if let Some(TokenTree::Ident(name)) = trees.next() {
+        if name.to_string() != "mut" {
+            Diagnostic::spanned(name.span(), Level::Error, format!("`{name}` must be mutable"))
+                .span_help(name.span().start(), "add `mut` here")
+                .emit();
+        }
+    }
+    TokenStream::new()
+}
@@ -0,0 +1,6 @@
+// include file for is-included.rs
+
+const _: () = assert!(first_is_included!(a));
+
+// Tokens passed through `macro_rules!` still point into this file.
+const _: () = assert!(pass_through!(a));
@@ -0,0 +1,15 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_span)]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+#[proc_macro]
+pub fn first_is_included(input: TokenStream) -> TokenStream {
+    let first = input.into_iter().next().unwrap();
+    first.span().is_included().to_string().parse().unwrap()
+}
@@ -0,0 +1,25 @@
+// force-host
+// no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_diagnostic, proc_macro_tool_lint)]
+
+extern crate proc_macro;
+
+use proc_macro::{Diagnostic, Level, Span, TokenStream};
+
+#[proc_macro]
+pub fn warn_tool_lint(_input: TokenStream) -> TokenStream {
+    let mut diagnostic = Diagnostic::spanned(Span::call_site(), Level::Warning, "tool lint");
+    diagnostic.set_tool_lint("my_tool", "my_lint");
+    diagnostic.emit();
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn warn_unregistered_tool_lint(_input: TokenStream) -> TokenStream {
+    let mut diagnostic = Diagnostic::spanned(Span::call_site(), Level::Warning, "tool lint");
+    diagnostic.set_tool_lint("other_tool", "my_lint");
+    diagnostic.emit();
+    TokenStream::new()
+}
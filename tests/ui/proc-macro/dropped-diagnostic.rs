@@ -0,0 +1,10 @@
+// check-pass
+// aux-build:dropped-diagnostic.rs
+
+// A diagnostic that a proc macro drops without emitting it is not reported.
+
+extern crate dropped_diagnostic;
+
+dropped_diagnostic::drop_diagnostic!();
+
+fn main() {}
@@ -0,0 +1,14 @@
+// revisions: human json
+// check-pass
+// aux-build:future-incompatible.rs
+//[json] compile-flags: --json=future-incompat --error-format=json
+
+// Diagnostics marked as future incompatible are repeated in the future
+// incompatibility report, under the name of their tool lint.
+
+extern crate future_incompatible;
+
+future_incompatible::old_syntax!(old);
+//~^ WARN `old_syntax!` is deprecated
+
+fn main() {}
@@ -0,0 +1,27 @@
+// check-pass
+// aux-build:input-token-count.rs
+
+// `input_token_count` counts the tokens as the macro sees them.
+
+#![allow(dead_code)]
+
+extern crate input_token_count;
+
+use input_token_count::{count, count_attr};
+
+const _: () = assert!(count!() == 0);
+const _: () = assert!(count!(a 1 "b") == 3);
+// Groups count as one token in addition to their contents.
+const _: () = assert!(count!((a [b]) {}) == 5);
+// Operators and lifetimes are split into puncts.
+const _: () = assert!(count!(a += 'b ..= c::d) == 12);
+// A doc comment is passed as `#`, `[`..`]` and `doc = "..."`.
+const _: () = assert!(count!(/// a
+) == 5);
+
+// Both the arguments and the item are counted.
+#[count_attr(x, y)]
+fn f() {}
+const _: () = assert!(COUNT == 3 + 4);
+
+fn main() {}
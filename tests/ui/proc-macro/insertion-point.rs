@@ -0,0 +1,10 @@
+// aux-build:insertion-point.rs
+
+// `Span::start` points right before a token, where text can be inserted.
+
+extern crate insertion_point;
+
+insertion_point::require_mut!(let mut x = 1;);
+insertion_point::require_mut!(let x = 1;); //~ ERROR `x` must be mutable
+
+fn main() {}
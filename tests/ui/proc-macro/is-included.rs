@@ -0,0 +1,19 @@
+// check-pass
+// aux-build:is-included.rs
+
+// `Span::is_included` is true for tokens from files pulled in by `include!`.
+
+extern crate is_included;
+
+use is_included::first_is_included;
+
+macro_rules! pass_through {
+    ($($t:tt)*) => { first_is_included!($($t)*) };
+}
+
+const _: () = assert!(!first_is_included!(a));
+const _: () = assert!(!pass_through!(a));
+
+include!("auxiliary/is-included-inc.rs");
+
+fn main() {}
@@ -0,0 +1,51 @@
+// aux-build:tool-lint.rs
+
+// Lint attributes around a proc macro invocation apply to the diagnostics it
+// marks as tool lints.
+
+#![feature(lint_reasons, register_tool)]
+#![allow(dead_code)]
+#![register_tool(my_tool)]
+
+extern crate tool_lint;
+
+use tool_lint::warn_tool_lint;
+
+warn_tool_lint!(); //~ WARN tool lint
+
+#[allow(my_tool::my_lint)]
+mod allowed {
+    super::warn_tool_lint!();
+
+    #[warn(my_tool::my_lint)]
+    fn warned() {
+        super::warn_tool_lint!(); //~ WARN tool lint
+    }
+}
+
+#[deny(my_tool::my_lint)]
+mod denied {
+    super::warn_tool_lint!(); //~ ERROR tool lint
+
+    fn allowed() {
+        #[allow(my_tool::my_lint)]
+        super::warn_tool_lint!();
+    }
+}
+
+#[deny(warnings)]
+mod warnings_denied {
+    super::warn_tool_lint!(); //~ ERROR tool lint
+}
+
+#[expect(my_tool::my_lint)]
+mod expected {
+    super::warn_tool_lint!();
+
+    fn expected_invocation() {
+        #[expect(my_tool::my_lint)]
+        super::warn_tool_lint!();
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// aux-build:tool-lint.rs
+
+// Lint attributes for the tool lints of a tool that isn't registered have no
+// effect on the diagnostics a proc macro marks as such.
+
+extern crate tool_lint;
+
+#[allow(other_tool::my_lint)]
+//~^ ERROR unknown tool name `other_tool` found in scoped lint
+//~| ERROR unknown tool name `other_tool` found in scoped lint
+mod allowed {
+    tool_lint::warn_unregistered_tool_lint!(); //~ WARN tool lint
+}
+
+fn main() {}